@@ -0,0 +1,260 @@
+use macroquad::prelude::*;
+use std::collections::HashMap;
+
+/// A named run of frames on a sprite sheet.
+#[derive(Clone, Copy, Debug)]
+struct Clip {
+    first: usize,
+    last: usize,
+    fps: f32,
+    looping: bool, // Otherwise it holds on the last frame.
+}
+
+/// A texture cut into equal frames, read left to right and top to bottom,
+/// with named clips. The clips come from a metadata file next to the sheet,
+/// e.g. `mathnaut.anim` for `mathnaut.png`:
+///
+/// ```text
+/// frame_size = 128x128
+/// # clip = first-last fps loop|once
+/// idle = 0-3 6 loop
+/// tumble = 12-15 10 once
+/// ```
+///
+/// Without a metadata file the whole texture is a single frame, and every
+/// clip shows it.
+pub struct SpriteSheet {
+    pub texture: Texture2D,
+    frame_width: f32,
+    frame_height: f32,
+    clips: HashMap<String, Clip>,
+}
+
+impl SpriteSheet {
+    pub fn new(texture: Texture2D, metadata: Option<&str>) -> SpriteSheet {
+        let mut sheet = SpriteSheet {
+            frame_width: texture.width(),
+            frame_height: texture.height(),
+            texture,
+            clips: HashMap::new(),
+        };
+        for line in metadata.unwrap_or_default().lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                eprintln!("Ignoring animation line: {}", line);
+                continue;
+            };
+            let (key, value) = (key.trim(), value.trim());
+            if key == "frame_size" {
+                match parse_size(value) {
+                    Some((w, h)) => (sheet.frame_width, sheet.frame_height) = (w, h),
+                    None => eprintln!("Bad frame size: {}", value),
+                }
+            } else {
+                match parse_clip(value) {
+                    Some(clip) => {
+                        sheet.clips.insert(key.to_string(), clip);
+                    }
+                    None => eprintln!("Bad animation clip {}: {}", key, value),
+                }
+            }
+        }
+        sheet
+    }
+
+    /// Whether the sheet has any clips, rather than being a single picture.
+    pub fn is_animated(&self) -> bool {
+        !self.clips.is_empty()
+    }
+
+    pub fn frame_size(&self) -> Vec2 {
+        vec2(self.frame_width, self.frame_height)
+    }
+
+    /// The part of the texture showing `clip` after `time` seconds. Clips
+    /// the sheet doesn't have show its first frame.
+    pub fn frame(&self, clip: &str, time: f32) -> Rect {
+        let index = self.clips.get(clip).map_or(0, |clip| clip.frame(time));
+        let columns = ((self.texture.width() / self.frame_width) as usize).max(1);
+        frame_rect(index, columns, self.frame_width, self.frame_height)
+    }
+
+    /// Draws the current frame of `anim`. `params.dest_size` defaults to
+    /// the frame size; `params.source` is replaced.
+    pub fn draw(&self, anim: &Animation, x: f32, y: f32, color: Color, params: DrawTextureParams) {
+        self.draw_frame(anim.clip, anim.time, x, y, color, params);
+    }
+
+    pub fn draw_frame(
+        &self,
+        clip: &str,
+        time: f32,
+        x: f32,
+        y: f32,
+        color: Color,
+        params: DrawTextureParams,
+    ) {
+        draw_texture_ex(
+            &self.texture,
+            x,
+            y,
+            color,
+            DrawTextureParams {
+                source: Some(self.frame(clip, time)),
+                dest_size: params.dest_size.or(Some(self.frame_size())),
+                ..params
+            },
+        );
+    }
+}
+
+/// Which clip a sprite is playing, and for how long.
+pub struct Animation {
+    pub clip: &'static str,
+    pub time: f32,
+}
+
+impl Animation {
+    pub fn new(clip: &'static str) -> Animation {
+        Animation { clip, time: 0.0 }
+    }
+
+    /// Switches to `clip`, starting it from the beginning unless it is
+    /// already playing.
+    pub fn play(&mut self, clip: &'static str) {
+        if self.clip != clip {
+            self.clip = clip;
+            self.time = 0.0;
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.time += dt;
+    }
+}
+
+impl Clip {
+    // The sheet frame showing after `time` seconds.
+    fn frame(&self, time: f32) -> usize {
+        let count = self.last - self.first + 1;
+        let step = (time.max(0.0) * self.fps) as usize;
+        self.first
+            + if self.looping {
+                step % count
+            } else {
+                step.min(count - 1)
+            }
+    }
+}
+
+// Where frame `index` sits on a sheet `columns` frames wide.
+fn frame_rect(index: usize, columns: usize, width: f32, height: f32) -> Rect {
+    Rect::new(
+        (index % columns) as f32 * width,
+        (index / columns) as f32 * height,
+        width,
+        height,
+    )
+}
+
+// "128x128"
+fn parse_size(value: &str) -> Option<(f32, f32)> {
+    let (w, h) = value.split_once('x')?;
+    let w: f32 = w.trim().parse().ok()?;
+    let h: f32 = h.trim().parse().ok()?;
+    (w > 0.0 && h > 0.0).then_some((w, h))
+}
+
+// "0-3 6 loop"
+fn parse_clip(value: &str) -> Option<Clip> {
+    let mut parts = value.split_whitespace();
+    let frames = parts.next()?;
+    let (first, last) = match frames.split_once('-') {
+        Some((first, last)) => (first.parse().ok()?, last.parse().ok()?),
+        None => {
+            let frame = frames.parse().ok()?;
+            (frame, frame)
+        }
+    };
+    let fps: f32 = parts.next()?.parse().ok()?;
+    let looping = match parts.next() {
+        Some("loop") | None => true,
+        Some("once") => false,
+        Some(_) => return None,
+    };
+    (first <= last && fps > 0.0).then_some(Clip {
+        first,
+        last,
+        fps,
+        looping,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_frame_sizes() {
+        assert_eq!(parse_size("128x64"), Some((128.0, 64.0)));
+        assert_eq!(parse_size(" 32 x 32 "), Some((32.0, 32.0)));
+        assert_eq!(parse_size("128"), None);
+        assert_eq!(parse_size("0x128"), None);
+        assert_eq!(parse_size("wide x tall"), None);
+    }
+
+    #[test]
+    fn parses_clips() {
+        let clip = parse_clip("0-3 6 loop").unwrap();
+        assert_eq!(
+            (clip.first, clip.last, clip.fps, clip.looping),
+            (0, 3, 6.0, true)
+        );
+        let clip = parse_clip("12-15 10 once").unwrap();
+        assert_eq!((clip.first, clip.last, clip.looping), (12, 15, false));
+        // A single frame, looping when the mode is left out.
+        let clip = parse_clip("5 1").unwrap();
+        assert_eq!((clip.first, clip.last, clip.looping), (5, 5, true));
+    }
+
+    #[test]
+    fn rejects_bad_clips() {
+        assert!(parse_clip("3-0 6 loop").is_none()); // Reversed range.
+        assert!(parse_clip("0-3 0 loop").is_none());
+        assert!(parse_clip("0-3 6 bounce").is_none());
+        assert!(parse_clip("0-3").is_none());
+        assert!(parse_clip("a-b 6").is_none());
+        assert!(parse_clip("").is_none());
+    }
+
+    #[test]
+    fn looping_clips_wrap_and_others_hold() {
+        let looping = parse_clip("4-7 10 loop").unwrap();
+        assert_eq!(looping.frame(0.0), 4);
+        assert_eq!(looping.frame(0.35), 7);
+        assert_eq!(looping.frame(0.45), 4);
+        let once = parse_clip("4-7 10 once").unwrap();
+        assert_eq!(once.frame(0.35), 7);
+        assert_eq!(once.frame(5.0), 7);
+        assert_eq!(once.frame(-1.0), 4);
+    }
+
+    #[test]
+    fn frames_read_left_to_right_then_down() {
+        assert_eq!(
+            frame_rect(0, 4, 128.0, 64.0),
+            Rect::new(0.0, 0.0, 128.0, 64.0)
+        );
+        assert_eq!(
+            frame_rect(3, 4, 128.0, 64.0),
+            Rect::new(384.0, 0.0, 128.0, 64.0)
+        );
+        assert_eq!(
+            frame_rect(5, 4, 128.0, 64.0),
+            Rect::new(128.0, 64.0, 128.0, 64.0)
+        );
+    }
+}
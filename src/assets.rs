@@ -0,0 +1,234 @@
+use macroquad::prelude::*;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use crate::animation::SpriteSheet;
+use crate::{draw_centered_text, theme, view, SCREEN_HEIGHT, SCREEN_WIDTH};
+
+/// Environment variable that points the game at a different assets folder.
+const ASSETS_ENV: &str = "MATHNAUTS_ASSETS";
+
+/// Finds the assets folder: `$MATHNAUTS_ASSETS` if set, then `assets`
+/// next to the executable (or a few folders up, for `cargo run`), then
+/// `assets` in the working directory.
+pub fn asset_dir() -> &'static PathBuf {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    DIR.get_or_init(|| {
+        if let Some(dir) = std::env::var_os(ASSETS_ENV) {
+            return PathBuf::from(dir);
+        }
+        if let Ok(exe) = std::env::current_exe() {
+            if let Some(dir) = exe
+                .ancestors()
+                .skip(1)
+                .take(4)
+                .map(|dir| dir.join("assets"))
+                .find(|dir| dir.is_dir())
+            {
+                return dir;
+            }
+        }
+        PathBuf::from("assets")
+    })
+}
+
+/// Path of a file inside the assets folder, e.g. `asset_path("alien.png")`.
+pub fn asset_path(name: &str) -> String {
+    asset_dir().join(name).to_string_lossy().into_owned()
+}
+
+// Files built into the executable with the `embed-assets` feature, so the
+// game runs without its assets folder. Add new assets here to embed them.
+#[cfg(feature = "embed-assets")]
+const EMBEDDED: &[(&str, &[u8])] = &[
+    ("mathnaut.png", include_bytes!("../assets/mathnaut.png")),
+    ("flame.png", include_bytes!("../assets/flame.png")),
+    ("shuttle.png", include_bytes!("../assets/shuttle.png")),
+    ("alien.png", include_bytes!("../assets/alien.png")),
+    ("mathnaut.anim", include_bytes!("../assets/mathnaut.anim")),
+    ("flame.anim", include_bytes!("../assets/flame.anim")),
+    ("alien.anim", include_bytes!("../assets/alien.anim")),
+    (
+        "fonts/dyslexic.ttf",
+        include_bytes!("../assets/fonts/dyslexic.ttf"),
+    ),
+    (
+        "audio/sfx/thrust.wav",
+        include_bytes!("../assets/audio/sfx/thrust.wav"),
+    ),
+    (
+        "audio/sfx/correct.wav",
+        include_bytes!("../assets/audio/sfx/correct.wav"),
+    ),
+    (
+        "audio/sfx/wrong.wav",
+        include_bytes!("../assets/audio/sfx/wrong.wav"),
+    ),
+    (
+        "audio/sfx/life_lost.wav",
+        include_bytes!("../assets/audio/sfx/life_lost.wav"),
+    ),
+    (
+        "audio/sfx/alien_landing.wav",
+        include_bytes!("../assets/audio/sfx/alien_landing.wav"),
+    ),
+    (
+        "audio/sfx/game_over.wav",
+        include_bytes!("../assets/audio/sfx/game_over.wav"),
+    ),
+    (
+        "audio/music/menu.wav",
+        include_bytes!("../assets/audio/music/menu.wav"),
+    ),
+    (
+        "audio/music/playing.wav",
+        include_bytes!("../assets/audio/music/playing.wav"),
+    ),
+    (
+        "audio/music/boss.wav",
+        include_bytes!("../assets/audio/music/boss.wav"),
+    ),
+    (
+        "audio/music/results.wav",
+        include_bytes!("../assets/audio/music/results.wav"),
+    ),
+];
+
+#[cfg(not(feature = "embed-assets"))]
+const EMBEDDED: &[(&str, &[u8])] = &[];
+
+/// Reads a file from the assets folder. A file on disk always wins, so
+/// custom themes can override the embedded copy.
+pub async fn load_bytes(name: &str) -> Result<Vec<u8>, macroquad::Error> {
+    match load_file(&asset_path(name)).await {
+        Ok(bytes) => Ok(bytes),
+        Err(err) => match EMBEDDED.iter().find(|(embedded, _)| *embedded == name) {
+            Some((_, bytes)) => Ok(bytes.to_vec()),
+            None => Err(err),
+        },
+    }
+}
+
+/// The game's sprites. Any that failed to load are replaced by placeholders.
+pub struct Textures {
+    pub astronaut: SpriteSheet,
+    pub flame: SpriteSheet,
+    pub shuttle: Texture2D,
+    pub alien: SpriteSheet,
+}
+
+/// Loads assets one step at a time, drawing a progress screen in between,
+/// and collects what went wrong so it can be shown later.
+pub struct Loader {
+    done: usize,
+    total: usize,
+    pub errors: Vec<String>,
+}
+
+impl Loader {
+    pub fn new(total: usize) -> Loader {
+        Loader {
+            done: 0,
+            total,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Shows the loading screen for the step about to start.
+    pub async fn step(&mut self, what: &str) {
+        view::begin();
+        let palette = theme::palette();
+        clear_background(palette.background);
+        draw_centered_text("Loading...", SCREEN_HEIGHT / 2.0 - 60.0, 50, palette.text);
+        let bar_width = 500.0;
+        let bar_x = (SCREEN_WIDTH - bar_width) / 2.0;
+        let bar_y = SCREEN_HEIGHT / 2.0 - 15.0;
+        let fraction = self.done as f32 / self.total as f32;
+        draw_rectangle(bar_x, bar_y, bar_width * fraction, 30.0, palette.text);
+        draw_rectangle_lines(bar_x, bar_y, bar_width, 30.0, 3.0, palette.text);
+        draw_centered_text(what, SCREEN_HEIGHT / 2.0 + 60.0, 28, palette.muted);
+        view::end();
+        self.done += 1;
+        next_frame().await;
+    }
+
+    /// Loads a sprite, or makes a `color` placeholder of `size` pixels if it
+    /// can't be loaded.
+    pub async fn texture(&mut self, name: &str, size: u16, color: Color) -> Texture2D {
+        match self.load_texture(name).await {
+            Some(texture) => texture,
+            None => placeholder(size, color),
+        }
+    }
+
+    /// Loads a sprite sheet and the animation metadata next to it, e.g.
+    /// `alien.anim` for `alien.png`. Without metadata it is a single frame.
+    /// A placeholder ignores the metadata, whose frames wouldn't fit it.
+    pub async fn sheet(&mut self, name: &str, size: u16, color: Color) -> SpriteSheet {
+        let Some(texture) = self.load_texture(name).await else {
+            return SpriteSheet::new(placeholder(size, color), None);
+        };
+        let metadata_name = match name.rsplit_once('.') {
+            Some((stem, _)) => format!("{}.anim", stem),
+            None => format!("{}.anim", name),
+        };
+        let metadata = load_bytes(&metadata_name)
+            .await
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok());
+        SpriteSheet::new(texture, metadata.as_deref())
+    }
+
+    // Loads a sprite, recording the problem if it can't be loaded.
+    async fn load_texture(&mut self, name: &str) -> Option<Texture2D> {
+        self.step(&format!("Loading {}", name)).await;
+        let image = match load_bytes(name).await {
+            Ok(bytes) => Image::from_file_with_format(&bytes, None),
+            Err(err) => Err(err),
+        };
+        match image {
+            Ok(image) => {
+                let texture = Texture2D::from_image(&image);
+                texture.set_filter(FilterMode::Nearest);
+                Some(texture)
+            }
+            Err(err) => {
+                eprintln!("Could not load {}: {}", name, err);
+                self.errors.push(format!("Missing {}", name));
+                None
+            }
+        }
+    }
+}
+
+// A filled circle on a transparent square, standing in for a missing sprite.
+fn placeholder(size: u16, color: Color) -> Texture2D {
+    let mut image = Image::gen_image_color(size, size, Color::new(0.0, 0.0, 0.0, 0.0));
+    let radius = size as f32 / 2.0;
+    for y in 0..size as u32 {
+        for x in 0..size as u32 {
+            let dx = x as f32 + 0.5 - radius;
+            let dy = y as f32 + 0.5 - radius;
+            if dx * dx + dy * dy <= radius * radius {
+                image.set_pixel(x, y, color);
+            }
+        }
+    }
+    let texture = Texture2D::from_image(&image);
+    texture.set_filter(FilterMode::Nearest);
+    texture
+}
+
+/// Lists asset problems along the bottom of the screen, so a broken
+/// install is visible without crashing.
+pub fn draw_errors(errors: &[String]) {
+    if errors.is_empty() {
+        return;
+    }
+    let text = format!(
+        "{} (looked in {}) - using placeholders",
+        errors.join(", "),
+        asset_dir().display()
+    );
+    draw_centered_text(&text, SCREEN_HEIGHT - 12.0, 18, theme::palette().bad);
+}
@@ -0,0 +1,180 @@
+use macroquad::audio::{
+    load_sound_from_bytes, play_sound, set_sound_volume, stop_sound, PlaySoundParams, Sound,
+};
+use macroquad::prelude::*;
+use std::collections::HashMap;
+
+use crate::assets;
+
+/// Folder in the assets folder the sound effects and music are loaded from. Each file may be
+/// `.ogg` or `.wav`; missing files are simply not played.
+const AUDIO_DIR: &str = "audio";
+
+/// One-off sound effects.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Sfx {
+    Thrust, // Looped while the jetpack fires.
+    Correct,
+    Wrong,
+    LifeLost,
+    AlienLanding,
+    GameOver,
+}
+
+impl Sfx {
+    const ALL: [Sfx; 6] = [
+        Sfx::Thrust,
+        Sfx::Correct,
+        Sfx::Wrong,
+        Sfx::LifeLost,
+        Sfx::AlienLanding,
+        Sfx::GameOver,
+    ];
+
+    fn file(self) -> &'static str {
+        match self {
+            Sfx::Thrust => "sfx/thrust",
+            Sfx::Correct => "sfx/correct",
+            Sfx::Wrong => "sfx/wrong",
+            Sfx::LifeLost => "sfx/life_lost",
+            Sfx::AlienLanding => "sfx/alien_landing",
+            Sfx::GameOver => "sfx/game_over",
+        }
+    }
+}
+
+/// Background music, one looping track per part of the game.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Music {
+    Menu,
+    Playing,
+    Boss,
+    Results,
+}
+
+impl Music {
+    const ALL: [Music; 4] = [Music::Menu, Music::Playing, Music::Boss, Music::Results];
+
+    fn file(self) -> &'static str {
+        match self {
+            Music::Menu => "music/menu",
+            Music::Playing => "music/playing",
+            Music::Boss => "music/boss",
+            Music::Results => "music/results",
+        }
+    }
+}
+
+/// Plays sound effects and keeps the right music track looping.
+pub struct Audio {
+    sfx: HashMap<Sfx, Sound>,
+    music: HashMap<Music, Sound>,
+    current_music: Option<Music>,
+    thrusting: bool,
+}
+
+impl Audio {
+    /// Loads every sound that exists. Without the `audio` feature nothing is
+    /// loaded and the game stays silent.
+    pub async fn load() -> Audio {
+        let mut sfx = HashMap::new();
+        let mut music = HashMap::new();
+        if cfg!(feature = "audio") {
+            for effect in Sfx::ALL {
+                if let Some(sound) = load_clip(effect.file()).await {
+                    sfx.insert(effect, sound);
+                }
+            }
+            for track in Music::ALL {
+                if let Some(sound) = load_clip(track.file()).await {
+                    music.insert(track, sound);
+                }
+            }
+        }
+        Audio {
+            sfx,
+            music,
+            current_music: None,
+            thrusting: false,
+        }
+    }
+
+    pub fn play(&self, effect: Sfx, volume: f32) {
+        if let Some(sound) = self.sfx.get(&effect) {
+            play_sound(
+                sound,
+                PlaySoundParams {
+                    looped: false,
+                    volume,
+                },
+            );
+        }
+    }
+
+    /// Starts or stops the looping jetpack sound to match the flame.
+    pub fn set_thrust(&mut self, thrusting: bool, volume: f32) {
+        if thrusting == self.thrusting {
+            return;
+        }
+        self.thrusting = thrusting;
+        if let Some(sound) = self.sfx.get(&Sfx::Thrust) {
+            if thrusting {
+                play_sound(
+                    sound,
+                    PlaySoundParams {
+                        looped: true,
+                        volume,
+                    },
+                );
+            } else {
+                stop_sound(sound);
+            }
+        }
+    }
+
+    /// Switches to `track`, leaving it alone if it is already playing.
+    /// Call it every frame with the current state's track.
+    pub fn set_music(&mut self, track: Music, volume: f32) {
+        if self.current_music == Some(track) {
+            return;
+        }
+        if let Some(sound) = self.current_music.and_then(|t| self.music.get(&t)) {
+            stop_sound(sound);
+        }
+        self.current_music = Some(track);
+        if let Some(sound) = self.music.get(&track) {
+            play_sound(
+                sound,
+                PlaySoundParams {
+                    looped: true,
+                    volume,
+                },
+            );
+        }
+    }
+
+    /// Applies new volumes to the sounds that are already playing.
+    pub fn set_volumes(&self, music_volume: f32, sfx_volume: f32) {
+        if let Some(sound) = self.current_music.and_then(|t| self.music.get(&t)) {
+            set_sound_volume(sound, music_volume);
+        }
+        if let Some(sound) = self.sfx.get(&Sfx::Thrust).filter(|_| self.thrusting) {
+            set_sound_volume(sound, sfx_volume);
+        }
+    }
+}
+
+// Loads `name.ogg`, or `name.wav` if there is no Ogg file.
+async fn load_clip(name: &str) -> Option<Sound> {
+    for extension in ["ogg", "wav"] {
+        let path = format!("{}/{}.{}", AUDIO_DIR, name, extension);
+        let Ok(bytes) = assets::load_bytes(&path).await else {
+            continue;
+        };
+        match load_sound_from_bytes(&bytes).await {
+            Ok(sound) => return Some(sound),
+            Err(err) => eprintln!("Could not load {}: {}", path, err),
+        }
+    }
+    None
+}
@@ -0,0 +1,296 @@
+use macroquad::prelude::*;
+use std::collections::HashMap;
+
+use crate::input::Action;
+use crate::storage;
+
+const BINDINGS_FILE: &str = "bindings.cfg";
+
+/// Ready-made keyboard layouts the settings screen can switch between.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Layout {
+    Arrows,
+    Wasd,
+    OneHanded, // Everything within reach of the left hand.
+}
+
+impl Layout {
+    pub const ALL: [Layout; 3] = [Layout::Arrows, Layout::Wasd, Layout::OneHanded];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Layout::Arrows => "Arrow keys",
+            Layout::Wasd => "WASD",
+            Layout::OneHanded => "One-handed (left)",
+        }
+    }
+
+    fn keys(self, action: Action) -> Vec<KeyCode> {
+        use KeyCode::*;
+        let keys: &[KeyCode] = match (self, action) {
+            (Layout::Arrows, Action::MoveLeft) => &[Left],
+            (Layout::Arrows, Action::MoveRight) => &[Right],
+            (Layout::Arrows, Action::Thrust | Action::Up) => &[Up],
+            (Layout::Arrows, Action::Down) => &[Down],
+            (Layout::Arrows, Action::Addition) => &[A],
+            (Layout::Arrows, Action::Subtraction) => &[S],
+            (Layout::Arrows, Action::Multiplication) => &[M],
+            (Layout::Arrows, Action::Division) => &[D],
+            (Layout::Arrows, Action::Mixed) => &[X],
+            (Layout::Wasd, Action::Addition) => &[H],
+            (Layout::Wasd, Action::Subtraction) => &[J],
+            (Layout::Wasd, Action::Multiplication) => &[K],
+            (Layout::Wasd, Action::Division) => &[L],
+            (Layout::Wasd, Action::Mixed) => &[N],
+            (Layout::OneHanded, Action::Addition) => &[Z],
+            (Layout::OneHanded, Action::Subtraction) => &[X],
+            (Layout::OneHanded, Action::Multiplication) => &[C],
+            (Layout::OneHanded, Action::Division) => &[V],
+            (Layout::OneHanded, Action::Mixed) => &[B],
+            (Layout::OneHanded, Action::Easy) => &[Key1],
+            (Layout::OneHanded, Action::Medium) => &[Key2],
+            (Layout::OneHanded, Action::Hard) => &[Key3],
+            (Layout::OneHanded, Action::VeryHard) => &[Key4],
+            (Layout::OneHanded, Action::Campaign) => &[G],
+            (Layout::OneHanded, Action::Confirm) => &[Space, Enter],
+            (Layout::OneHanded, Action::Back) => &[Q, Escape],
+            (Layout::OneHanded, Action::Pause) => &[Tab, Escape],
+            (Layout::OneHanded, Action::Hint) => &[E],
+            // WASD movement, shared by both left-hand layouts.
+            (_, Action::MoveLeft) => &[A],
+            (_, Action::MoveRight) => &[D],
+            (_, Action::Thrust | Action::Up) => &[W],
+            (_, Action::Down) => &[S],
+            // The split keyboard for two players: WASD and the arrow keys.
+            (_, Action::OneLeft) => &[A],
+            (_, Action::OneRight) => &[D],
+            (_, Action::OneThrust) => &[W],
+            (_, Action::OneDown) => &[S],
+            (_, Action::TwoLeft) => &[Left],
+            (_, Action::TwoRight) => &[Right],
+            (_, Action::TwoThrust) => &[Up],
+            (_, Action::TwoDown) => &[Down],
+            // Keys that are the same everywhere else.
+            (_, Action::Confirm) => &[Enter, KpEnter, Space],
+            (_, Action::Back) => &[Escape],
+            (_, Action::Pause) => &[Escape, P],
+            (_, Action::Fullscreen) => &[F11],
+            (_, Action::Hint) => &[H],
+            (_, Action::Easy) => &[Key0],
+            (_, Action::Medium) => &[Key1],
+            (_, Action::Hard) => &[Key2],
+            (_, Action::VeryHard) => &[Key3],
+            (_, Action::Campaign) => &[C],
+        };
+        keys.to_vec()
+    }
+}
+
+/// Which keyboard keys trigger each action.
+///
+/// Saved to `bindings.cfg` in the config folder, one action per line:
+/// `move_left = A, Left`.
+#[derive(PartialEq)]
+pub struct Bindings {
+    keys: HashMap<Action, Vec<KeyCode>>,
+}
+
+impl Bindings {
+    pub fn from_layout(layout: Layout) -> Bindings {
+        let keys = Action::ALL
+            .iter()
+            .map(|&action| (action, layout.keys(action)))
+            .collect();
+        Bindings { keys }
+    }
+
+    /// Loads the saved bindings on top of the arrow-key layout, so actions
+    /// missing from the file keep their default keys.
+    pub fn load() -> Bindings {
+        let mut bindings = Bindings::from_layout(Layout::Arrows);
+        let Some(contents) = storage::read_config_file(BINDINGS_FILE) else {
+            return bindings;
+        };
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((id, keys)) = line.split_once('=') else {
+                continue;
+            };
+            let Some(action) = Action::ALL.iter().find(|a| a.id() == id.trim()) else {
+                eprintln!("Unknown action in {}: {}", BINDINGS_FILE, id.trim());
+                continue;
+            };
+            let keys: Vec<KeyCode> = keys
+                .split(',')
+                .filter_map(|k| parse_key(k.trim()))
+                .collect();
+            if !keys.is_empty() {
+                bindings.keys.insert(*action, keys);
+            }
+        }
+        bindings
+    }
+
+    pub fn save(&self) {
+        let mut contents = String::from("# Mathnauts key bindings: action = key, key\n");
+        for action in Action::ALL {
+            let keys: Vec<String> = self
+                .keys(action)
+                .iter()
+                .map(|k| format!("{:?}", k))
+                .collect();
+            contents.push_str(&format!("{} = {}\n", action.id(), keys.join(", ")));
+        }
+        storage::write_config_file(BINDINGS_FILE, &contents);
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        self.keys.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Binds `action` to a single key, replacing its previous keys.
+    pub fn set(&mut self, action: Action, key: KeyCode) {
+        self.keys.insert(action, vec![key]);
+    }
+
+    /// Human-readable list of the keys for an action, e.g. "A / Left".
+    pub fn label(&self, action: Action) -> String {
+        let names: Vec<String> = self.keys(action).iter().map(|&k| key_name(k)).collect();
+        if names.is_empty() {
+            "(none)".to_string()
+        } else {
+            names.join(" / ")
+        }
+    }
+
+    /// The first key of an action, for short on-screen hints.
+    pub fn short_label(&self, action: Action) -> String {
+        self.keys(action)
+            .first()
+            .map(|&k| key_name(k))
+            .unwrap_or_else(|| "?".to_string())
+    }
+}
+
+/// Display name of a key: "Key3" becomes "3", everything else keeps its name.
+pub fn key_name(key: KeyCode) -> String {
+    let name = format!("{:?}", key);
+    match name.strip_prefix("Key") {
+        Some(digit) => digit.to_string(),
+        None => name,
+    }
+}
+
+// Every key that can be bound, used to turn saved names back into keys.
+const BINDABLE_KEYS: &[KeyCode] = {
+    use KeyCode::*;
+    &[
+        Space,
+        Apostrophe,
+        Comma,
+        Minus,
+        Period,
+        Slash,
+        Key0,
+        Key1,
+        Key2,
+        Key3,
+        Key4,
+        Key5,
+        Key6,
+        Key7,
+        Key8,
+        Key9,
+        Semicolon,
+        Equal,
+        A,
+        B,
+        C,
+        D,
+        E,
+        F,
+        G,
+        H,
+        I,
+        J,
+        K,
+        L,
+        M,
+        N,
+        O,
+        P,
+        Q,
+        R,
+        S,
+        T,
+        U,
+        V,
+        W,
+        X,
+        Y,
+        Z,
+        LeftBracket,
+        Backslash,
+        RightBracket,
+        GraveAccent,
+        Escape,
+        Enter,
+        Tab,
+        Backspace,
+        Insert,
+        Delete,
+        Right,
+        Left,
+        Down,
+        Up,
+        PageUp,
+        PageDown,
+        Home,
+        End,
+        F1,
+        F2,
+        F3,
+        F4,
+        F5,
+        F6,
+        F7,
+        F8,
+        F9,
+        F10,
+        F11,
+        F12,
+        Kp0,
+        Kp1,
+        Kp2,
+        Kp3,
+        Kp4,
+        Kp5,
+        Kp6,
+        Kp7,
+        Kp8,
+        Kp9,
+        KpDecimal,
+        KpDivide,
+        KpMultiply,
+        KpSubtract,
+        KpAdd,
+        KpEnter,
+        KpEqual,
+        LeftShift,
+        LeftControl,
+        LeftAlt,
+        RightShift,
+        RightControl,
+        RightAlt,
+    ]
+};
+
+fn parse_key(name: &str) -> Option<KeyCode> {
+    BINDABLE_KEYS
+        .iter()
+        .copied()
+        .find(|k| format!("{:?}", k) == name)
+}
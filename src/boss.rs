@@ -0,0 +1,178 @@
+use macroquad::prelude::*;
+
+use ::rand as ext_rand;
+use ext_rand::seq::IndexedRandom;
+
+use crate::animation::SpriteSheet;
+use crate::campaign::Mission;
+use crate::{generate_question, question_for_fact, Question, Struggles, GROUND_Y};
+
+/// What happens to a boss when the player gets a question wrong.
+#[derive(Clone, Copy, Debug)]
+pub enum BossPenalty {
+    /// The boss recovers to full health, so the hits must come in a row.
+    Heal,
+    /// The boss keeps its damage but descends faster.
+    SpeedUp,
+}
+
+/// Per-mission boss settings.
+pub struct BossConfig {
+    pub name: &'static str,
+    /// Correct answers needed to defeat the boss.
+    pub health: u32,
+    /// Descent speed in pixels per second.
+    pub speed: f32,
+    pub penalty: BossPenalty,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BossState {
+    Descending,
+    Hurt(f32),     // Knocked back after a correct answer; seconds left.
+    Enraged(f32),  // Flashing after a wrong answer; seconds left.
+    Defeated(f32), // Spinning away; seconds left.
+}
+
+// Boss size and how long each animation state lasts.
+const BOSS_SIZE: f32 = 300.0;
+const HURT_TIME: f32 = 0.4;
+const ENRAGED_TIME: f32 = 0.8;
+pub const DEFEATED_TIME: f32 = 1.5;
+// How far a correct answer pushes the boss back up, in pixels per second of `HURT_TIME`.
+const KNOCKBACK_SPEED: f32 = 150.0;
+// Speed multiplier applied by `BossPenalty::SpeedUp`.
+const SPEED_UP_FACTOR: f32 = 1.25;
+
+pub struct Boss {
+    pub name: &'static str,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub speed: f32,
+    pub health: u32,
+    pub max_health: u32,
+    pub state: BossState,
+    penalty: BossPenalty,
+    anim_time: f32,
+}
+
+impl Boss {
+    pub fn new(config: &BossConfig) -> Boss {
+        Boss {
+            name: config.name,
+            x: -40.0,
+            y: -BOSS_SIZE / 2.0,
+            width: BOSS_SIZE,
+            height: BOSS_SIZE,
+            speed: config.speed,
+            health: config.health,
+            max_health: config.health,
+            state: BossState::Descending,
+            penalty: config.penalty,
+            anim_time: 0.0,
+        }
+    }
+
+    /// Advances the animation timers and moves the boss down the screen.
+    pub fn update(&mut self, dt: f32) {
+        self.anim_time += dt;
+        self.state = match self.state {
+            BossState::Hurt(t) if t > dt => {
+                self.y = (self.y - KNOCKBACK_SPEED * dt).max(-BOSS_SIZE / 2.0);
+                BossState::Hurt(t - dt)
+            }
+            BossState::Enraged(t) if t > dt => BossState::Enraged(t - dt),
+            BossState::Defeated(t) => BossState::Defeated((t - dt).max(0.0)),
+            _ => BossState::Descending,
+        };
+        if matches!(self.state, BossState::Descending | BossState::Enraged(_)) {
+            self.y += self.speed * dt;
+        }
+    }
+
+    pub fn has_landed(&self) -> bool {
+        self.y + self.height >= GROUND_Y
+    }
+
+    pub fn is_defeated(&self) -> bool {
+        self.health == 0
+    }
+
+    /// Sends the boss back to the top of the screen after it lands.
+    pub fn restart_descent(&mut self) {
+        self.y = -BOSS_SIZE / 2.0;
+    }
+
+    /// A correct answer takes one point of health.
+    pub fn hit(&mut self) {
+        self.health = self.health.saturating_sub(1);
+        self.state = if self.health == 0 {
+            BossState::Defeated(DEFEATED_TIME)
+        } else {
+            BossState::Hurt(HURT_TIME)
+        };
+    }
+
+    /// A wrong answer (or a landing) heals the boss or speeds it up.
+    pub fn punish(&mut self) {
+        match self.penalty {
+            BossPenalty::Heal => self.health = self.max_health,
+            BossPenalty::SpeedUp => self.speed *= SPEED_UP_FACTOR,
+        }
+        self.state = BossState::Enraged(ENRAGED_TIME);
+    }
+
+    /// Draws the boss's descend clip, with its current state's effect on top.
+    pub fn draw(&self, sheet: &SpriteSheet) {
+        let t = self.anim_time;
+        let (offset_x, offset_y, scale, rotation, color) = match self.state {
+            // Gentle hovering bob.
+            BossState::Descending => (0.0, (t * 3.0).sin() * 6.0, 1.0, 0.0, WHITE),
+            // Shake and flash red.
+            BossState::Hurt(_) => ((t * 60.0).sin() * 8.0, 0.0, 1.0, 0.0, RED),
+            // Pulse bigger and glow orange.
+            BossState::Enraged(_) => (0.0, 0.0, 1.0 + (t * 20.0).sin().abs() * 0.08, 0.0, ORANGE),
+            // Spin, shrink and fade away.
+            BossState::Defeated(left) => {
+                let k = left / DEFEATED_TIME;
+                let fade = Color::new(1.0, 1.0, 1.0, k);
+                (0.0, 0.0, k, (1.0 - k) * 4.0 * std::f32::consts::PI, fade)
+            }
+        };
+        let w = self.width * scale;
+        let h = self.height * scale;
+        let x = self.x + (self.width - w) / 2.0 + offset_x;
+        let y = self.y + (self.height - h) / 2.0 + offset_y;
+        sheet.draw_frame(
+            "descend",
+            t,
+            x,
+            y,
+            color,
+            DrawTextureParams {
+                dest_size: Some(Vec2::new(w, h)),
+                rotation,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+/// Picks a boss question from the facts the player struggled with most this
+/// session. Only facts using the mission's operations are considered; with no
+/// struggles recorded yet, a slightly harder question from the mission is used.
+pub fn boss_question(struggles: &Struggles, mission: &Mission) -> Question {
+    let mut hardest: Vec<_> = struggles
+        .iter()
+        .filter(|(fact, _)| mission.operations.contains(&fact.op))
+        .collect();
+    hardest.sort_by(|a, b| b.1.cmp(a.1));
+    hardest.truncate(3);
+
+    match hardest.choose(&mut ext_rand::rng()) {
+        Some((fact, _)) => question_for_fact(**fact, fact.answer().max(5)),
+        None => generate_question(mission.difficulty + 500, mission.pick_operation()),
+    }
+}
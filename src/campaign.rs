@@ -0,0 +1,460 @@
+use macroquad::prelude::*;
+
+use ::rand as ext_rand;
+use ext_rand::seq::IndexedRandom;
+
+use crate::bindings::Bindings;
+use crate::boss::{Boss, BossConfig, BossPenalty};
+use crate::environment::Environment;
+use crate::input::Action;
+use crate::progress::Progress;
+use crate::theme;
+use crate::{draw_centered_text, Operation, SCREEN_HEIGHT, SCREEN_WIDTH};
+
+/// What a player has to achieve for a mission to count as passed.
+#[derive(Clone, Copy, Debug)]
+pub enum PassCondition {
+    /// Answer at least this many questions correctly.
+    Correct(u32),
+    /// Make no more than this many mistakes (wrong shuttles or landed aliens).
+    MaxMistakes(u32),
+}
+
+/// One numbered stop on the star map.
+pub struct Mission {
+    pub name: &'static str,
+    pub operations: &'static [Operation],
+    pub question_count: u32,
+    /// Score fed to `generate_question` to pick the number ranges.
+    pub difficulty: i32,
+    /// Alien descent speed at the start of the mission, in pixels per second.
+    pub alien_speed: f32,
+    /// Extra alien speed gained after every correct answer.
+    pub alien_speed_step: f32,
+    pub pass: PassCondition,
+    /// Where the mission is flown; sets the scenery and physics.
+    pub environment: Environment,
+    /// Boss fought after the last question, if the mission has one.
+    pub boss: Option<BossConfig>,
+}
+
+pub const MISSIONS: &[Mission] = &[
+    Mission {
+        name: "Launchpad of Addition",
+        operations: &[Operation::Addition],
+        question_count: 8,
+        difficulty: 0,
+        alien_speed: 40.0,
+        alien_speed_step: 2.0,
+        pass: PassCondition::Correct(5),
+        environment: Environment::Earth,
+        boss: None,
+    },
+    Mission {
+        name: "Crater of Subtraction",
+        operations: &[Operation::Subtraction],
+        question_count: 8,
+        difficulty: 0,
+        alien_speed: 45.0,
+        alien_speed_step: 2.0,
+        pass: PassCondition::Correct(5),
+        environment: Environment::Moon,
+        boss: Some(BossConfig {
+            name: "Minus Monster",
+            health: 3,
+            speed: 35.0,
+            penalty: BossPenalty::SpeedUp,
+        }),
+    },
+    Mission {
+        name: "Moon of Multiplication",
+        operations: &[Operation::Multiplication],
+        question_count: 10,
+        difficulty: 0,
+        alien_speed: 50.0,
+        alien_speed_step: 2.5,
+        pass: PassCondition::Correct(7),
+        environment: Environment::Moon,
+        boss: Some(BossConfig {
+            name: "Times Titan",
+            health: 3,
+            speed: 35.0,
+            penalty: BossPenalty::Heal,
+        }),
+    },
+    Mission {
+        name: "Comet of Division",
+        operations: &[Operation::Division],
+        question_count: 10,
+        difficulty: 0,
+        alien_speed: 50.0,
+        alien_speed_step: 2.5,
+        pass: PassCondition::Correct(7),
+        environment: Environment::Mars,
+        boss: Some(BossConfig {
+            name: "Divider Drone",
+            health: 3,
+            speed: 40.0,
+            penalty: BossPenalty::SpeedUp,
+        }),
+    },
+    Mission {
+        name: "Asteroids of Sums and Differences",
+        operations: &[Operation::Addition, Operation::Subtraction],
+        question_count: 12,
+        difficulty: 500,
+        alien_speed: 60.0,
+        alien_speed_step: 3.0,
+        pass: PassCondition::Correct(9),
+        environment: Environment::AsteroidBelt,
+        boss: Some(BossConfig {
+            name: "Asteroid Brute",
+            health: 4,
+            speed: 40.0,
+            penalty: BossPenalty::Heal,
+        }),
+    },
+    Mission {
+        name: "Nebula of Products and Quotients",
+        operations: &[Operation::Multiplication, Operation::Division],
+        question_count: 12,
+        difficulty: 500,
+        alien_speed: 65.0,
+        alien_speed_step: 3.0,
+        pass: PassCondition::MaxMistakes(3),
+        environment: Environment::Mars,
+        boss: Some(BossConfig {
+            name: "Nebula Queen",
+            health: 4,
+            speed: 45.0,
+            penalty: BossPenalty::SpeedUp,
+        }),
+    },
+    Mission {
+        name: "Mixed Moons",
+        operations: &[
+            Operation::Addition,
+            Operation::Subtraction,
+            Operation::Multiplication,
+            Operation::Division,
+        ],
+        question_count: 15,
+        difficulty: 1000,
+        alien_speed: 75.0,
+        alien_speed_step: 3.5,
+        pass: PassCondition::MaxMistakes(3),
+        environment: Environment::Moon,
+        boss: Some(BossConfig {
+            name: "Moon Mauler",
+            health: 5,
+            speed: 50.0,
+            penalty: BossPenalty::Heal,
+        }),
+    },
+    Mission {
+        name: "Black Hole Finale",
+        operations: &[
+            Operation::Addition,
+            Operation::Subtraction,
+            Operation::Multiplication,
+            Operation::Division,
+        ],
+        question_count: 15,
+        difficulty: 1500,
+        alien_speed: 90.0,
+        alien_speed_step: 4.0,
+        pass: PassCondition::MaxMistakes(2),
+        environment: Environment::AsteroidBelt,
+        boss: Some(BossConfig {
+            name: "Black Hole Overlord",
+            health: 6,
+            speed: 55.0,
+            penalty: BossPenalty::Heal,
+        }),
+    },
+];
+
+impl Mission {
+    /// Picks the operation for the next question from this mission's set.
+    pub fn pick_operation(&self) -> Operation {
+        *self.operations.choose(&mut ext_rand::rng()).unwrap()
+    }
+
+    fn describe_operations(&self) -> String {
+        let names: Vec<&str> = self.operations.iter().map(|op| op.name()).collect();
+        names.join(", ")
+    }
+
+    fn describe_pass(&self) -> String {
+        match self.pass {
+            PassCondition::Correct(n) => format!("Pass: {} of {} correct", n, self.question_count),
+            PassCondition::MaxMistakes(n) => format!("Pass: no more than {} mistakes", n),
+        }
+    }
+}
+
+/// Tracks how the player is doing in the mission currently being flown.
+pub struct MissionRun {
+    pub index: usize,
+    pub asked: u32,
+    pub correct: u32,
+    pub mistakes: u32,
+    /// The boss currently being fought, once the regular questions are done.
+    pub boss: Option<Boss>,
+    pub boss_defeated: bool,
+}
+
+impl MissionRun {
+    pub fn new(index: usize) -> MissionRun {
+        MissionRun {
+            index,
+            asked: 0,
+            correct: 0,
+            mistakes: 0,
+            boss: None,
+            boss_defeated: false,
+        }
+    }
+
+    pub fn mission(&self) -> &'static Mission {
+        &MISSIONS[self.index]
+    }
+
+    /// A shuttle with the right answer was reached.
+    pub fn record_correct(&mut self) {
+        self.asked += 1;
+        self.correct += 1;
+    }
+
+    /// The alien landed before the question was answered.
+    pub fn record_missed(&mut self) {
+        self.asked += 1;
+        self.mistakes += 1;
+    }
+
+    /// A wrong shuttle was hit. The question stays open unless the right
+    /// answer is then revealed; see `record_revealed`.
+    pub fn record_wrong(&mut self) {
+        self.mistakes += 1;
+    }
+
+    /// The right answer was shown after a mistake and the game moves on,
+    /// so the question counts as asked.
+    pub fn record_revealed(&mut self) {
+        self.asked += 1;
+    }
+
+    fn questions_done(&self) -> bool {
+        self.asked >= self.mission().question_count
+    }
+
+    /// The regular questions are over and the boss should appear.
+    pub fn needs_boss(&self) -> bool {
+        self.questions_done()
+            && self.mission().boss.is_some()
+            && self.boss.is_none()
+            && !self.boss_defeated
+    }
+
+    /// Brings in this mission's boss.
+    pub fn start_boss(&mut self) {
+        if let Some(config) = &self.mission().boss {
+            self.boss = Some(Boss::new(config));
+        }
+    }
+
+    /// Marks the boss as beaten once its health has run out.
+    pub fn check_boss_defeated(&mut self) {
+        if self.boss.as_ref().is_some_and(|boss| boss.is_defeated()) {
+            self.boss_defeated = true;
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.questions_done() && (self.mission().boss.is_none() || self.boss_defeated)
+    }
+
+    /// Alien speed for the current point in the mission.
+    pub fn alien_speed(&self) -> f32 {
+        let mission = self.mission();
+        mission.alien_speed + self.correct as f32 * mission.alien_speed_step
+    }
+
+    /// Stars earned so far: 0 if the pass condition isn't met, otherwise
+    /// 3 for a flawless run, 2 for at most two mistakes, and 1 for a pass.
+    pub fn stars(&self) -> u8 {
+        let passed = match self.mission().pass {
+            PassCondition::Correct(n) => self.correct >= n,
+            PassCondition::MaxMistakes(n) => self.mistakes <= n,
+        };
+        if !passed {
+            0
+        } else if self.mistakes == 0 {
+            3
+        } else if self.mistakes <= 2 {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+/// Screen position of a mission's marker, winding left to right across the map.
+fn marker_position(index: usize) -> Vec2 {
+    let margin = 100.0;
+    let step = (SCREEN_WIDTH - 2.0 * margin) / (MISSIONS.len() - 1) as f32;
+    let wave = if index.is_multiple_of(2) { -60.0 } else { 60.0 };
+    vec2(
+        margin + step * index as f32,
+        SCREEN_HEIGHT / 2.0 - 40.0 + wave,
+    )
+}
+
+// Radius of a mission marker on the star map.
+const MARKER_RADIUS: f32 = 32.0;
+
+/// The mission whose marker contains `point`, if any.
+pub fn mission_at(point: Vec2) -> Option<usize> {
+    (0..MISSIONS.len()).find(|&i| marker_position(i).distance(point) <= MARKER_RADIUS + 6.0)
+}
+
+/// Tappable button in the top-left corner that leads back to the menu.
+pub fn back_button_rect() -> Rect {
+    Rect::new(20.0, 20.0, 140.0, 50.0)
+}
+
+// Earned stars are filled in, the rest are outlines.
+fn draw_stars(center_x: f32, y: f32, earned: u8, size: f32) {
+    let palette = theme::palette();
+    for i in 0..3 {
+        let x = center_x + (i as f32 - 1.0) * size * 2.2;
+        if i < earned {
+            draw_poly(x, y, 5, size, -90.0, palette.good);
+        } else {
+            draw_poly_lines(x, y, 5, size, -90.0, 2.0, palette.muted);
+        }
+    }
+}
+
+/// Draws the star map with the selected mission highlighted.
+pub fn draw_star_map(progress: &Progress, player_name: &str, selected: usize, bindings: &Bindings) {
+    let palette = theme::palette();
+    clear_background(palette.background);
+    draw_centered_text("Star Map", 80.0, 60, palette.text);
+    let back = back_button_rect();
+    draw_rectangle_lines(back.x, back.y, back.w, back.h, 2.0, palette.muted);
+    theme::draw_text("< Menu", back.x + 18.0, back.y + 34.0, 32, palette.muted);
+
+    // Paths between missions.
+    for i in 1..MISSIONS.len() {
+        let a = marker_position(i - 1);
+        let b = marker_position(i);
+        let color = if progress.is_unlocked(player_name, i) {
+            palette.text
+        } else {
+            palette.muted
+        };
+        draw_line(a.x, a.y, b.x, b.y, 4.0, color);
+    }
+
+    // Mission markers with their best stars underneath.
+    for i in 0..MISSIONS.len() {
+        let pos = marker_position(i);
+        let unlocked = progress.is_unlocked(player_name, i);
+        if i == selected {
+            draw_circle(pos.x, pos.y, 38.0, palette.good);
+        }
+        draw_circle(
+            pos.x,
+            pos.y,
+            MARKER_RADIUS,
+            if unlocked {
+                palette.ground
+            } else {
+                palette.muted
+            },
+        );
+        let label = (i + 1).to_string();
+        let dims = theme::measure_text(&label, 40);
+        theme::draw_text(
+            &label,
+            pos.x - dims.width / 2.0,
+            pos.y + dims.height / 2.0,
+            40,
+            palette.ground_text,
+        );
+        draw_stars(pos.x, pos.y + 52.0, progress.stars(player_name, i), 9.0);
+    }
+
+    // Details of the selected mission.
+    let mission = &MISSIONS[selected];
+    let details_y = SCREEN_HEIGHT - 220.0;
+    draw_centered_text(
+        &format!(
+            "Mission {}: {} ({})",
+            selected + 1,
+            mission.name,
+            mission.environment.name()
+        ),
+        details_y,
+        40,
+        palette.text,
+    );
+    draw_centered_text(
+        &format!(
+            "{}  -  {} questions  -  {}",
+            mission.describe_operations(),
+            mission.question_count,
+            mission.describe_pass()
+        ),
+        details_y + 45.0,
+        28,
+        palette.muted,
+    );
+    let prompt = if progress.is_unlocked(player_name, selected) {
+        format!(
+            "{}/{} or tap to choose, {} or tap again to launch",
+            bindings.short_label(Action::MoveLeft),
+            bindings.short_label(Action::MoveRight),
+            bindings.short_label(Action::Confirm).to_uppercase()
+        )
+    } else {
+        "Locked: pass the previous mission first".to_string()
+    };
+    draw_centered_text(&prompt, details_y + 110.0, 28, palette.muted);
+}
+
+/// Draws the results screen shown after a mission ends.
+pub fn draw_mission_complete(run: &MissionRun, stars: u8, bindings: &Bindings) {
+    let palette = theme::palette();
+    clear_background(palette.background);
+    let mission = run.mission();
+    draw_centered_text(
+        &format!("Mission {}: {}", run.index + 1, mission.name),
+        SCREEN_HEIGHT / 2.0 - 180.0,
+        40,
+        palette.text,
+    );
+    let (result, color) = if stars > 0 {
+        ("MISSION COMPLETE", palette.good)
+    } else {
+        ("MISSION FAILED", palette.bad)
+    };
+    draw_centered_text(result, SCREEN_HEIGHT / 2.0 - 100.0, 60, color);
+    draw_stars(SCREEN_WIDTH / 2.0, SCREEN_HEIGHT / 2.0 - 10.0, stars, 30.0);
+    draw_centered_text(
+        &format!("Correct: {}   Mistakes: {}", run.correct, run.mistakes),
+        SCREEN_HEIGHT / 2.0 + 80.0,
+        36,
+        palette.text,
+    );
+    draw_centered_text(
+        &format!(
+            "Press {} or tap to return to the star map",
+            bindings.short_label(Action::Confirm).to_uppercase()
+        ),
+        SCREEN_HEIGHT / 2.0 + 150.0,
+        30,
+        palette.muted,
+    );
+}
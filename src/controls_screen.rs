@@ -0,0 +1,212 @@
+use macroquad::prelude::*;
+
+use crate::bindings::{Bindings, Layout};
+use crate::input::{Action, Input};
+use crate::theme;
+use crate::{draw_centered_text, SCREEN_HEIGHT, SCREEN_WIDTH};
+
+/// What the controls screen wants the caller to do next.
+pub enum ControlsEvent {
+    None,
+    Close,
+}
+
+// Rows after the action list.
+const LAYOUT_ROW: usize = Action::ALL.len();
+const BACK_ROW: usize = LAYOUT_ROW + 1;
+const ROW_COUNT: usize = BACK_ROW + 1;
+
+const FIRST_ROW_Y: f32 = 115.0;
+const ROW_SPACING: f32 = 29.0;
+// Rows on screen at once; the list scrolls to keep the selection in view.
+const VISIBLE_ROWS: usize = 20;
+
+// The rectangle of the `slot`th row on screen.
+fn row_rect(slot: usize) -> Rect {
+    let y = FIRST_ROW_Y + slot as f32 * ROW_SPACING;
+    Rect::new(
+        SCREEN_WIDTH / 2.0 - 330.0,
+        y - 22.0,
+        660.0,
+        ROW_SPACING - 2.0,
+    )
+}
+
+// The preset `bindings` match, as an index into `Layout::ALL`, or None
+// once any key has been changed by hand.
+fn preset(bindings: &Bindings) -> Option<usize> {
+    Layout::ALL
+        .iter()
+        .position(|&layout| Bindings::from_layout(layout) == *bindings)
+}
+
+/// Lists every action with its keys and lets the player rebind them.
+pub struct ControlsScreen {
+    selected: usize,
+    first: usize,    // The row at the top of the screen.
+    capturing: bool, // Waiting for the new key of the selected action.
+    // A preset that would replace custom bindings, waiting to be confirmed.
+    pending: Option<usize>,
+}
+
+impl ControlsScreen {
+    pub fn new() -> ControlsScreen {
+        ControlsScreen {
+            selected: 0,
+            first: 0,
+            capturing: false,
+            pending: None,
+        }
+    }
+
+    pub fn update(&mut self, input: &mut Input) -> ControlsEvent {
+        if self.capturing {
+            if let Some(key) = get_last_key_pressed() {
+                // Escape cancels instead of being bound.
+                if key != KeyCode::Escape {
+                    input.bindings.set(Action::ALL[self.selected], key);
+                }
+                self.capturing = false;
+            } else if input.is_pressed(Action::Back) || input.tap().is_some() {
+                // Without a keyboard, a tap or a controller's Back cancels.
+                self.capturing = false;
+            }
+            return ControlsEvent::None;
+        }
+        if let Some(layout) = self.pending {
+            // Confirm or another tap on the row replaces the custom keys;
+            // anything else keeps them.
+            let layout_slot = LAYOUT_ROW - self.first;
+            if input.is_pressed(Action::Confirm) || input.tapped(row_rect(layout_slot)) {
+                input.bindings = Bindings::from_layout(Layout::ALL[layout]);
+                self.pending = None;
+            } else if input.tap().is_some()
+                || [
+                    Action::Back,
+                    Action::Up,
+                    Action::Down,
+                    Action::MoveLeft,
+                    Action::MoveRight,
+                ]
+                .iter()
+                .any(|&action| input.is_pressed(action))
+            {
+                self.pending = None;
+            }
+            return ControlsEvent::None;
+        }
+
+        if input.is_pressed(Action::Up) && self.selected > 0 {
+            self.selected -= 1;
+        } else if input.is_pressed(Action::Down) && self.selected + 1 < ROW_COUNT {
+            self.selected += 1;
+        }
+        let mut activate = input.is_pressed(Action::Confirm);
+        if let Some(slot) = (0..VISIBLE_ROWS).find(|&slot| input.tapped(row_rect(slot))) {
+            self.selected = self.first + slot;
+            activate = true;
+        }
+        self.first = self.first.clamp(
+            (self.selected + 1).saturating_sub(VISIBLE_ROWS),
+            self.selected,
+        );
+
+        match self.selected {
+            LAYOUT_ROW => {
+                let step = if input.is_pressed(Action::MoveLeft) {
+                    Some(Layout::ALL.len() - 1)
+                } else if input.is_pressed(Action::MoveRight) || activate {
+                    Some(1)
+                } else {
+                    None
+                };
+                if let Some(step) = step {
+                    match preset(&input.bindings) {
+                        Some(current) => {
+                            let next = (current + step) % Layout::ALL.len();
+                            input.bindings = Bindings::from_layout(Layout::ALL[next]);
+                        }
+                        // Custom keys would be lost, so ask first.
+                        None => self.pending = Some(if step == 1 { 0 } else { step }),
+                    }
+                }
+            }
+            BACK_ROW if activate => return self.close(input),
+            row if activate && row < LAYOUT_ROW => self.capturing = true,
+            _ => {}
+        }
+        if input.is_pressed(Action::Back) {
+            return self.close(input);
+        }
+        ControlsEvent::None
+    }
+
+    fn close(&mut self, input: &Input) -> ControlsEvent {
+        input.bindings.save();
+        self.selected = 0;
+        self.first = 0;
+        ControlsEvent::Close
+    }
+
+    pub fn draw(&self, bindings: &Bindings) {
+        let palette = theme::palette();
+        clear_background(palette.background);
+        draw_centered_text("Controls", 60.0, 50, palette.text);
+
+        let last = (self.first + VISIBLE_ROWS).min(ROW_COUNT);
+        for (slot, row) in (self.first..last).enumerate() {
+            let rect = row_rect(slot);
+            if row == self.selected {
+                draw_rectangle(
+                    rect.x,
+                    rect.y,
+                    rect.w,
+                    rect.h,
+                    Color::new(1.0, 1.0, 1.0, 0.5),
+                );
+            }
+            let y = rect.y + 21.0;
+            let (label, value) = match row {
+                LAYOUT_ROW => (
+                    "Layout preset".to_string(),
+                    match (self.pending, preset(bindings)) {
+                        (Some(layout), _) => format!("{}?", Layout::ALL[layout].name()),
+                        (None, Some(layout)) => format!("< {} >", Layout::ALL[layout].name()),
+                        (None, None) => "< Custom >".to_string(),
+                    },
+                ),
+                BACK_ROW => ("Save and go back".to_string(), String::new()),
+                _ => {
+                    let action = Action::ALL[row];
+                    let keys = if self.capturing && row == self.selected {
+                        "Press a key... (Escape or tap cancels)".to_string()
+                    } else {
+                        bindings.label(action)
+                    };
+                    (action.label().to_string(), keys)
+                }
+            };
+            theme::draw_text(&label, rect.x + 10.0, y, 26, palette.text);
+            theme::draw_text(&value, rect.x + 300.0, y, 26, palette.muted);
+        }
+
+        let confirm = bindings.short_label(Action::Confirm).to_uppercase();
+        let help = match self.pending {
+            Some(layout) => format!(
+                "Replace your custom keys with {}? {} or tap it again for yes, {} for no",
+                Layout::ALL[layout].name(),
+                confirm,
+                bindings.short_label(Action::Back).to_uppercase()
+            ),
+            None => format!(
+                "{}/{} to choose, {} to rebind, {}/{} on Layout to switch presets",
+                bindings.short_label(Action::Up),
+                bindings.short_label(Action::Down),
+                confirm,
+                bindings.short_label(Action::MoveLeft),
+                bindings.short_label(Action::MoveRight)
+            ),
+        };
+        draw_centered_text(&help, SCREEN_HEIGHT - 30.0, 22, palette.muted);
+    }
+}
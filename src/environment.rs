@@ -0,0 +1,246 @@
+use macroquad::prelude::*;
+
+use crate::physics::Profile;
+use crate::theme::Palette;
+use crate::SCREEN_WIDTH;
+
+/// Where a run takes place. Each environment has its own sky, scenery,
+/// ground and physics.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Environment {
+    Earth, // The launchpad.
+    Moon,
+    Mars,
+    AsteroidBelt,
+}
+
+// Colors an environment paints the play field with.
+struct Look {
+    sky_top: Color,
+    sky_bottom: Color,
+    far: Color,  // Clouds or stars.
+    near: Color, // Hills, craters or drifting rocks.
+    ground: Color,
+    ground_tile: Color,
+    text: Color,
+    muted: Color,
+    ground_text: Color,
+}
+
+// How far each background layer moves as the astronaut moves, as a
+// fraction of the astronaut's movement. Smaller is further away.
+const FAR_PARALLAX: f32 = 0.05;
+const NEAR_PARALLAX: f32 = 0.2;
+const GROUND_TILE_WIDTH: f32 = 64.0;
+
+impl Environment {
+    pub fn name(self) -> &'static str {
+        match self {
+            Environment::Earth => "Earth launchpad",
+            Environment::Moon => "Moon",
+            Environment::Mars => "Mars",
+            Environment::AsteroidBelt => "Asteroid belt",
+        }
+    }
+
+    /// Where an endless run is at `score`: it travels further out as the
+    /// score climbs through the levels.
+    pub fn for_score(score: i32) -> Environment {
+        match score {
+            ..500 => Environment::Earth,
+            500..1000 => Environment::Moon,
+            1000..1500 => Environment::Mars,
+            _ => Environment::AsteroidBelt,
+        }
+    }
+
+    /// How the astronaut moves here, unless the settings pick other physics.
+    pub fn physics(self) -> Profile {
+        match self {
+            Environment::Earth => Profile::Earth,
+            Environment::Moon => Profile::Moon,
+            Environment::Mars => Profile::Mars,
+            Environment::AsteroidBelt => Profile::ZeroG,
+        }
+    }
+
+    fn look(self) -> Look {
+        match self {
+            Environment::Earth => Look {
+                sky_top: Color::new(0.35, 0.60, 0.95, 1.0),
+                sky_bottom: Color::new(0.75, 0.88, 1.0, 1.0),
+                far: Color::new(1.0, 1.0, 1.0, 0.8),
+                near: Color::new(0.30, 0.50, 0.30, 1.0),
+                ground: Color::new(0.45, 0.45, 0.48, 1.0),
+                ground_tile: Color::new(0.38, 0.38, 0.42, 1.0),
+                text: BLACK,
+                muted: DARKGRAY,
+                ground_text: WHITE,
+            },
+            Environment::Moon => Look {
+                sky_top: Color::new(0.01, 0.01, 0.05, 1.0),
+                sky_bottom: Color::new(0.08, 0.08, 0.16, 1.0),
+                far: WHITE,
+                near: Color::new(0.35, 0.35, 0.38, 1.0),
+                ground: Color::new(0.60, 0.60, 0.62, 1.0),
+                ground_tile: Color::new(0.52, 0.52, 0.55, 1.0),
+                text: WHITE,
+                muted: LIGHTGRAY,
+                ground_text: BLACK,
+            },
+            Environment::Mars => Look {
+                sky_top: Color::new(0.55, 0.32, 0.22, 1.0),
+                sky_bottom: Color::new(0.90, 0.62, 0.42, 1.0),
+                far: Color::new(1.0, 0.9, 0.8, 0.6),
+                near: Color::new(0.55, 0.25, 0.12, 1.0),
+                ground: Color::new(0.62, 0.28, 0.12, 1.0),
+                ground_tile: Color::new(0.55, 0.23, 0.10, 1.0),
+                text: BLACK,
+                muted: Color::new(0.30, 0.12, 0.05, 1.0),
+                ground_text: WHITE,
+            },
+            Environment::AsteroidBelt => Look {
+                sky_top: Color::new(0.0, 0.0, 0.03, 1.0),
+                sky_bottom: Color::new(0.10, 0.04, 0.16, 1.0),
+                far: WHITE,
+                near: Color::new(0.45, 0.40, 0.36, 1.0),
+                ground: Color::new(0.36, 0.31, 0.28, 1.0),
+                ground_tile: Color::new(0.30, 0.26, 0.24, 1.0),
+                text: WHITE,
+                muted: LIGHTGRAY,
+                ground_text: WHITE,
+            },
+        }
+    }
+
+    /// `base` with the colors this environment's scenery needs. Lives and
+    /// the good/bad colors are left alone so the theme still tells them apart.
+    pub fn palette(self, base: Palette) -> Palette {
+        let look = self.look();
+        Palette {
+            background: look.sky_bottom,
+            text: look.text,
+            muted: look.muted,
+            ground: look.ground,
+            ground_text: look.ground_text,
+            ..base
+        }
+    }
+}
+
+// A repeatable pseudo-random number in 0..1 for scenery item `i`, so the
+// scenery stays put from frame to frame.
+fn scatter(i: u32, salt: u32) -> f32 {
+    let mut n = i.wrapping_mul(0x9E37_79B9) ^ salt.wrapping_mul(0x85EB_CA6B);
+    n ^= n >> 15;
+    n = n.wrapping_mul(0x2C1B_3C6D);
+    n ^= n >> 12;
+    (n & 0xFFFF) as f32 / 65535.0
+}
+
+// Wraps `x` into the screen plus a margin, so layers scroll forever.
+fn wrap(x: f32, margin: f32) -> f32 {
+    (x + margin).rem_euclid(SCREEN_WIDTH + 2.0 * margin) - margin
+}
+
+/// Draws the sky and the parallax layers above the ground at `ground_y`.
+/// `focus_x` is the astronaut's position; the layers shift against it.
+pub fn draw_background(environment: Environment, focus_x: f32, ground_y: f32) {
+    let look = environment.look();
+    let time = get_time() as f32;
+    let shift = focus_x - SCREEN_WIDTH / 2.0;
+
+    // Sky gradient in bands.
+    const BANDS: usize = 24;
+    let band_height = ground_y / BANDS as f32;
+    for i in 0..BANDS {
+        let k = i as f32 / (BANDS - 1) as f32;
+        let color = Color::new(
+            look.sky_top.r + (look.sky_bottom.r - look.sky_top.r) * k,
+            look.sky_top.g + (look.sky_bottom.g - look.sky_top.g) * k,
+            look.sky_top.b + (look.sky_bottom.b - look.sky_top.b) * k,
+            1.0,
+        );
+        draw_rectangle(
+            0.0,
+            i as f32 * band_height,
+            SCREEN_WIDTH,
+            band_height + 1.0,
+            color,
+        );
+    }
+
+    // Far layer: clouds on Earth, stars everywhere else.
+    let far_shift = shift * FAR_PARALLAX;
+    match environment {
+        Environment::Earth => {
+            for i in 0..6 {
+                let x = wrap(scatter(i, 1) * SCREEN_WIDTH - far_shift + time * 8.0, 120.0);
+                let y = 60.0 + scatter(i, 2) * ground_y * 0.4;
+                let size = 30.0 + scatter(i, 3) * 30.0;
+                draw_circle(x, y, size, look.far);
+                draw_circle(x + size * 0.9, y + 6.0, size * 0.8, look.far);
+                draw_circle(x - size * 0.9, y + 8.0, size * 0.7, look.far);
+            }
+        }
+        _ => {
+            for i in 0..120 {
+                let x = wrap(scatter(i, 1) * SCREEN_WIDTH - far_shift, 4.0);
+                let y = scatter(i, 2) * ground_y;
+                let twinkle = 0.5 + 0.5 * (time * 2.0 + i as f32).sin().abs();
+                let color = Color::new(look.far.r, look.far.g, look.far.b, look.far.a * twinkle);
+                draw_circle(x, y, 0.8 + scatter(i, 3) * 1.4, color);
+            }
+        }
+    }
+
+    // Near layer: hills and craters sit on the horizon; asteroids drift past.
+    let near_shift = shift * NEAR_PARALLAX;
+    match environment {
+        Environment::AsteroidBelt => {
+            for i in 0..10 {
+                let x = wrap(
+                    scatter(i, 4) * SCREEN_WIDTH - near_shift - time * 15.0,
+                    60.0,
+                );
+                let y = 150.0 + scatter(i, 5) * (ground_y - 200.0);
+                let radius = 10.0 + scatter(i, 6) * 25.0;
+                draw_poly(x, y, 7, radius, time * 10.0 + i as f32 * 40.0, look.near);
+            }
+        }
+        _ => {
+            let peaks = 9;
+            let spacing = (SCREEN_WIDTH + 240.0) / peaks as f32;
+            for i in 0..peaks {
+                let x = wrap(i as f32 * spacing - near_shift, 120.0);
+                let height = 50.0 + scatter(i as u32, 7) * 90.0;
+                let half_width = spacing * 0.8;
+                draw_triangle(
+                    vec2(x - half_width, ground_y),
+                    vec2(x, ground_y - height),
+                    vec2(x + half_width, ground_y),
+                    look.near,
+                );
+            }
+        }
+    }
+}
+
+/// Draws tiles over the ground strip.
+pub fn draw_ground_tiles(environment: Environment, ground_y: f32, height: f32) {
+    let look = environment.look();
+    let tiles = (SCREEN_WIDTH / GROUND_TILE_WIDTH).ceil() as u32;
+    for i in 0..tiles {
+        let x = i as f32 * GROUND_TILE_WIDTH;
+        if i % 2 == 0 {
+            draw_rectangle(x, ground_y, GROUND_TILE_WIDTH, height, look.ground_tile);
+        }
+        // A few pebbles or craters per tile.
+        for j in 0..3 {
+            let px = x + scatter(i * 3 + j, 8) * GROUND_TILE_WIDTH;
+            let py = ground_y + 8.0 + scatter(i * 3 + j, 9) * (height - 16.0);
+            draw_circle(px, py, 2.0 + scatter(i * 3 + j, 10) * 3.0, look.near);
+        }
+    }
+    draw_line(0.0, ground_y, SCREEN_WIDTH, ground_y, 3.0, look.near);
+}
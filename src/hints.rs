@@ -0,0 +1,277 @@
+use macroquad::prelude::*;
+
+use crate::manipulatives::{self, MAX_DOTS_SIDE};
+use crate::pause_menu;
+use crate::theme;
+use crate::{Fact, Operation};
+
+/// Free hints at the start of every run; after that each one costs points.
+pub const FREE_HINTS: u32 = 3;
+pub const HINT_COST: i32 = 50;
+
+// The panel covers the score and question at the top of the screen, left
+// of the hint and pause buttons and above the answers, so it never hides
+// the shuttles or the hazards.
+const PANEL_TOP: f32 = 10.0;
+const PANEL_BOTTOM_LIMIT: f32 = 140.0;
+const PANEL_LEFT: f32 = 20.0;
+const PADDING: f32 = 10.0;
+const VISUAL_WIDTH: f32 = 260.0;
+
+/// The hint button, just left of the pause button.
+pub fn button_rect() -> Rect {
+    let pause = pause_menu::button_rect();
+    Rect::new(pause.x - pause.w - 10.0, pause.y, pause.w, pause.h)
+}
+
+/// The hint budget for a run, and which question the hint is showing for.
+pub struct Hints {
+    free_left: u32,
+    shown_for: Option<Fact>,
+}
+
+impl Hints {
+    pub fn new() -> Hints {
+        Hints {
+            free_left: FREE_HINTS,
+            shown_for: None,
+        }
+    }
+
+    /// Shows the hint for `fact`, using a free hint if there are any left
+    /// and otherwise taking `HINT_COST` from `score`. Returns false if the
+    /// hint can't be paid for. Asking again for the same question is free.
+    pub fn request(&mut self, fact: Fact, score: &mut i32) -> bool {
+        if self.shown_for == Some(fact) {
+            return true;
+        }
+        if self.free_left > 0 {
+            self.free_left -= 1;
+        } else if *score >= HINT_COST {
+            *score -= HINT_COST;
+        } else {
+            return false;
+        }
+        self.shown_for = Some(fact);
+        true
+    }
+
+    /// Draws the hint panel if a hint was bought for `fact`.
+    pub fn draw(&self, fact: Fact) {
+        if self.shown_for != Some(fact) {
+            return;
+        }
+        let palette = theme::palette();
+        let lines = steps(fact);
+        let size = theme::scaled(22);
+        let line_height = size as f32 + 6.0;
+        let text_height = lines.len() as f32 * line_height;
+        let (x, y) = (PANEL_LEFT, PANEL_TOP);
+        let width = button_rect().x - 20.0 - x;
+        let height =
+            (text_height.max(visual_height(fact)) + 2.0 * PADDING).min(PANEL_BOTTOM_LIMIT - y);
+        let mut background = palette.background;
+        background.a = 0.9;
+        draw_rectangle(x, y, width, height, background);
+        draw_rectangle_lines(x, y, width, height, 3.0, palette.muted);
+
+        for (i, line) in lines.iter().enumerate() {
+            let line_y = y + PADDING + line_height * (i as f32 + 1.0) - 6.0;
+            theme::draw_text(line, x + 20.0, line_y, size, palette.text);
+        }
+        let visual = Rect::new(
+            x + width - VISUAL_WIDTH - 20.0,
+            y + PADDING,
+            VISUAL_WIDTH,
+            height - 2.0 * PADDING,
+        );
+        match fact.op {
+            Operation::Addition => draw_number_line(fact.a, fact.b, visual),
+            Operation::Subtraction => draw_number_line(fact.a, -fact.b, visual),
+            Operation::Multiplication => manipulatives::draw_dots(fact.a, fact.b, visual),
+            Operation::Division | Operation::Mixed => {}
+        }
+    }
+
+    /// The "?" button, with the free hints left or the price underneath.
+    pub fn draw_button(&self, score: i32) {
+        let palette = theme::palette();
+        let rect = button_rect();
+        let affordable = self.free_left > 0 || score >= HINT_COST;
+        let mut fill = palette.background;
+        fill.a = 0.8;
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, fill);
+        draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2.0, palette.text);
+        let mark = theme::measure_text("?", 40);
+        theme::draw_text(
+            "?",
+            rect.x + (rect.w - mark.width) / 2.0,
+            rect.y + 38.0,
+            40,
+            if affordable {
+                palette.text
+            } else {
+                palette.muted
+            },
+        );
+        let price = if self.free_left > 0 {
+            format!("{} free", self.free_left)
+        } else {
+            format!("-{}", HINT_COST)
+        };
+        let size = theme::scaled(20);
+        let width = theme::measure_text(&price, size).width;
+        let color = if affordable {
+            palette.text
+        } else {
+            palette.muted
+        };
+        theme::draw_text(
+            &price,
+            rect.x + (rect.w - width) / 2.0,
+            rect.y + rect.h + size as f32 * 0.9,
+            size,
+            color,
+        );
+    }
+}
+
+/// The hint's steps, one line each. They lead up to the answer without
+/// giving it away.
+fn steps(fact: Fact) -> Vec<String> {
+    let (a, b) = (fact.a, fact.b);
+    match fact.op {
+        Operation::Addition | Operation::Subtraction => {
+            let (direction, sign) = if fact.op == Operation::Addition {
+                ("right", "+")
+            } else {
+                ("left", "-")
+            };
+            let jumps: Vec<String> = place_value_parts(b)
+                .iter()
+                .map(|part| format!("{}{}", sign, part))
+                .collect();
+            vec![
+                format!("Start at {} on the number line.", a),
+                format!("Jump {} by {}.", direction, jumps.join(", then ")),
+                "Where do you land?".to_string(),
+            ]
+        }
+        // With no rows or a single row there is nothing to add or count by.
+        Operation::Multiplication if a <= 1 => vec![
+            format!(
+                "{} × {} means {} {} of {}.",
+                a,
+                b,
+                a,
+                if a == 1 { "row" } else { "rows" },
+                b
+            ),
+            if a == 1 {
+                format!("How many are in just one row of {}?", b)
+            } else {
+                "How many are in no rows at all?".to_string()
+            },
+        ],
+        Operation::Multiplication => {
+            let sum = if a <= 6 {
+                vec![b.to_string(); a.max(0) as usize].join(" + ")
+            } else {
+                format!("{} + {} + ... ({} times)", b, b, a)
+            };
+            let counted: Vec<String> = (1..a.clamp(1, 6)).map(|i| (i * b).to_string()).collect();
+            vec![
+                format!("{} × {} means {} rows of {}.", a, b, a, b),
+                format!("Add them up: {}", sum),
+                format!("Count by {}s: {}, ...", b, counted.join(", ")),
+            ]
+        }
+        Operation::Division => vec![
+            "Use the fact family:".to_string(),
+            format!("{} × ? = {}   and   ? × {} = {}", b, a, b, a),
+            format!("{} ÷ {} = ?   and   {} ÷ ? = {}", a, b, a, b),
+            format!("Count by {}s until you reach {}.", b, a),
+        ],
+        Operation::Mixed => unreachable!("facts always use a single operation"),
+    }
+}
+
+/// Splits a jump into hundreds, tens and ones, e.g. 127 into 100, 20 and 7.
+fn place_value_parts(n: i32) -> Vec<i32> {
+    let n = n.abs();
+    let parts: Vec<i32> = [n / 100 * 100, n / 10 % 10 * 10, n % 10]
+        .into_iter()
+        .filter(|&part| part > 0)
+        .collect();
+    if parts.is_empty() {
+        vec![0]
+    } else {
+        parts
+    }
+}
+
+fn visual_height(fact: Fact) -> f32 {
+    match fact.op {
+        Operation::Addition | Operation::Subtraction => 90.0,
+        Operation::Multiplication if fact.a.max(fact.b) <= MAX_DOTS_SIDE => 110.0,
+        _ => 0.0,
+    }
+}
+
+/// A number line from `start` with one arc per place-value jump of
+/// `change`. The landing point is marked "?".
+fn draw_number_line(start: i32, change: i32, area: Rect) {
+    let palette = theme::palette();
+    let end = start + change;
+    let (low, high) = (start.min(end), start.max(end));
+    let span = (high - low).max(1) as f32;
+    let line_y = area.y + area.h - 25.0;
+    let to_x = |n: i32| area.x + 10.0 + (n - low) as f32 / span * (area.w - 20.0);
+    draw_line(area.x, line_y, area.x + area.w, line_y, 2.0, palette.text);
+
+    let mut at = start;
+    tick(to_x(at), line_y, &at.to_string());
+    for part in place_value_parts(change) {
+        let next = at + part * change.signum();
+        let (x0, x1) = (to_x(at), to_x(next));
+        draw_arc((x0 + x1) / 2.0, line_y, (x1 - x0).abs() / 2.0, palette.good);
+        let label = format!("{}{}", if change < 0 { "-" } else { "+" }, part);
+        let width = theme::measure_text(&label, 18).width;
+        let top = line_y - (x1 - x0).abs() / 2.0 - 6.0;
+        theme::draw_text(
+            &label,
+            (x0 + x1 - width) / 2.0,
+            top.max(area.y + 14.0),
+            18,
+            palette.good,
+        );
+        at = next;
+        let label = if at == end {
+            "?".to_string()
+        } else {
+            at.to_string()
+        };
+        tick(to_x(at), line_y, &label);
+    }
+}
+
+fn tick(x: f32, y: f32, label: &str) {
+    let palette = theme::palette();
+    draw_line(x, y - 6.0, x, y + 6.0, 2.0, palette.text);
+    let width = theme::measure_text(label, 18).width;
+    theme::draw_text(label, x - width / 2.0, y + 22.0, 18, palette.text);
+}
+
+// The top half of a circle, flattened to fit above the number line.
+fn draw_arc(center_x: f32, y: f32, radius: f32, color: Color) {
+    const SEGMENTS: usize = 16;
+    let height = radius.min(40.0);
+    let point = |i: usize| {
+        let angle = std::f32::consts::PI * i as f32 / SEGMENTS as f32;
+        vec2(center_x - radius * angle.cos(), y - height * angle.sin())
+    };
+    for i in 0..SEGMENTS {
+        let (p0, p1) = (point(i), point(i + 1));
+        draw_line(p0.x, p0.y, p1.x, p1.y, 2.0, color);
+    }
+}
@@ -0,0 +1,375 @@
+use macroquad::prelude::*;
+use std::collections::HashSet;
+
+use crate::bindings::Bindings;
+use crate::view;
+
+/// Things the player can do, independent of the device used to do them.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    Thrust,
+    Up, // Menu navigation.
+    Down,
+    Confirm,
+    Back,
+    Pause,
+    Fullscreen,
+    Hint,
+    // Menu shortcuts for the operation...
+    Addition,
+    Subtraction,
+    Multiplication,
+    Division,
+    Mixed,
+    // ...and for starting a game.
+    Easy,
+    Medium,
+    Hard,
+    VeryHard,
+    Campaign,
+    // Each player's movement in two-player runs, on a split keyboard.
+    OneLeft,
+    OneRight,
+    OneThrust,
+    OneDown,
+    TwoLeft,
+    TwoRight,
+    TwoThrust,
+    TwoDown,
+}
+
+impl Action {
+    pub const ALL: [Action; 28] = [
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Thrust,
+        Action::Up,
+        Action::Down,
+        Action::Confirm,
+        Action::Back,
+        Action::Pause,
+        Action::Fullscreen,
+        Action::Hint,
+        Action::Addition,
+        Action::Subtraction,
+        Action::Multiplication,
+        Action::Division,
+        Action::Mixed,
+        Action::Easy,
+        Action::Medium,
+        Action::Hard,
+        Action::VeryHard,
+        Action::Campaign,
+        Action::OneLeft,
+        Action::OneRight,
+        Action::OneThrust,
+        Action::OneDown,
+        Action::TwoLeft,
+        Action::TwoRight,
+        Action::TwoThrust,
+        Action::TwoDown,
+    ];
+
+    /// Name used in the bindings file.
+    pub fn id(self) -> &'static str {
+        match self {
+            Action::MoveLeft => "move_left",
+            Action::MoveRight => "move_right",
+            Action::Thrust => "thrust",
+            Action::Up => "menu_up",
+            Action::Down => "menu_down",
+            Action::Confirm => "confirm",
+            Action::Back => "back",
+            Action::Pause => "pause",
+            Action::Fullscreen => "fullscreen",
+            Action::Hint => "hint",
+            Action::Addition => "addition",
+            Action::Subtraction => "subtraction",
+            Action::Multiplication => "multiplication",
+            Action::Division => "division",
+            Action::Mixed => "mixed",
+            Action::Easy => "easy",
+            Action::Medium => "medium",
+            Action::Hard => "hard",
+            Action::VeryHard => "very_hard",
+            Action::Campaign => "campaign",
+            Action::OneLeft => "player1_move_left",
+            Action::OneRight => "player1_move_right",
+            Action::OneThrust => "player1_thrust",
+            Action::OneDown => "player1_down",
+            Action::TwoLeft => "player2_move_left",
+            Action::TwoRight => "player2_move_right",
+            Action::TwoThrust => "player2_thrust",
+            Action::TwoDown => "player2_down",
+        }
+    }
+
+    /// Name shown on the controls screen.
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::MoveLeft => "Move left",
+            Action::MoveRight => "Move right",
+            Action::Thrust => "Jetpack thrust",
+            Action::Up => "Menu up",
+            Action::Down => "Menu down",
+            Action::Confirm => "Confirm",
+            Action::Back => "Back",
+            Action::Pause => "Pause",
+            Action::Fullscreen => "Toggle fullscreen",
+            Action::Hint => "Show a hint",
+            Action::Addition => "Addition",
+            Action::Subtraction => "Subtraction",
+            Action::Multiplication => "Multiplication",
+            Action::Division => "Division",
+            Action::Mixed => "Mixed",
+            Action::Easy => "Start Easy",
+            Action::Medium => "Start Medium",
+            Action::Hard => "Start Hard",
+            Action::VeryHard => "Start Very Hard",
+            Action::Campaign => "Open Campaign",
+            Action::OneLeft => "Player 1 left",
+            Action::OneRight => "Player 1 right",
+            Action::OneThrust => "Player 1 thrust",
+            Action::OneDown => "Player 1 down",
+            Action::TwoLeft => "Player 2 left",
+            Action::TwoRight => "Player 2 right",
+            Action::TwoThrust => "Player 2 thrust",
+            Action::TwoDown => "Player 2 down",
+        }
+    }
+}
+
+/// Whose controls move an astronaut.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Seat {
+    Solo, // The only player, on the regular bindings.
+    One,
+    Two,
+}
+
+// Movement actions that can belong to one seat in a two-player run.
+const SEAT_ACTIONS: [Action; 4] = [
+    Action::MoveLeft,
+    Action::MoveRight,
+    Action::Thrust,
+    Action::Down,
+];
+
+// The split keyboard: each seat's own bindable action for every entry in
+// `SEAT_ACTIONS`.
+const SEAT_BINDINGS: [[Action; 4]; 2] = [
+    [
+        Action::OneLeft,
+        Action::OneRight,
+        Action::OneThrust,
+        Action::OneDown,
+    ],
+    [
+        Action::TwoLeft,
+        Action::TwoRight,
+        Action::TwoThrust,
+        Action::TwoDown,
+    ],
+];
+
+/// Merges keyboard and (with the `gamepad` feature) controller input into
+/// actions, and tracks mouse and touch pointers. Call `update` once at the
+/// start of every frame.
+pub struct Input {
+    pub bindings: Bindings,
+    down: HashSet<Action>,
+    previous: HashSet<Action>,
+    // Movement held by each seat of a two-player run, from the split
+    // keyboard and one gamepad per seat.
+    seats: [HashSet<Action>; 2],
+    // Positions (in virtual screen coordinates) of every finger, or the
+    // pressed mouse, this frame.
+    pointers: Vec<Vec2>,
+    // Positions where a finger or mouse press started this frame.
+    taps: Vec<Vec2>,
+    #[cfg(feature = "gamepad")]
+    gilrs: Option<gilrs::Gilrs>,
+}
+
+impl Input {
+    pub fn new(bindings: Bindings) -> Input {
+        Input {
+            bindings,
+            down: HashSet::new(),
+            previous: HashSet::new(),
+            seats: [HashSet::new(), HashSet::new()],
+            pointers: Vec::new(),
+            taps: Vec::new(),
+            #[cfg(feature = "gamepad")]
+            gilrs: match gilrs::Gilrs::new() {
+                Ok(gilrs) => Some(gilrs),
+                Err(err) => {
+                    eprintln!("Gamepad support unavailable: {}", err);
+                    None
+                }
+            },
+        }
+    }
+
+    /// Reads this frame's keyboard, gamepad, mouse and touch state.
+    pub fn update(&mut self) {
+        self.previous = std::mem::take(&mut self.down);
+        for action in Action::ALL {
+            if self
+                .bindings
+                .keys(action)
+                .iter()
+                .any(|&key| is_key_down(key))
+            {
+                self.down.insert(action);
+            }
+        }
+        for (seat, bound) in self.seats.iter_mut().zip(SEAT_BINDINGS) {
+            seat.clear();
+            for (action, own) in SEAT_ACTIONS.into_iter().zip(bound) {
+                if self.down.contains(&own) {
+                    seat.insert(action);
+                }
+            }
+        }
+        #[cfg(feature = "gamepad")]
+        if let Some(gilrs) = &mut self.gilrs {
+            gamepad::poll(gilrs, &mut self.down, &mut self.seats);
+        }
+
+        // Touches also arrive as simulated mouse events, so the mouse is
+        // only read when no finger is on the screen.
+        self.pointers.clear();
+        self.taps.clear();
+        let touches = touches();
+        if touches.is_empty() {
+            let mouse = view::to_virtual(Vec2::from(mouse_position()));
+            if is_mouse_button_down(MouseButton::Left) {
+                self.pointers.push(mouse);
+            }
+            if is_mouse_button_pressed(MouseButton::Left) {
+                self.taps.push(mouse);
+            }
+        } else {
+            for touch in touches {
+                match touch.phase {
+                    TouchPhase::Started => {
+                        self.pointers.push(view::to_virtual(touch.position));
+                        self.taps.push(view::to_virtual(touch.position));
+                    }
+                    TouchPhase::Stationary | TouchPhase::Moved => {
+                        self.pointers.push(view::to_virtual(touch.position))
+                    }
+                    TouchPhase::Ended | TouchPhase::Cancelled => {}
+                }
+            }
+        }
+    }
+
+    /// Holds an action down for this frame, e.g. from an on-screen button.
+    pub fn hold(&mut self, action: Action) {
+        self.down.insert(action);
+    }
+
+    /// The action is held this frame.
+    pub fn is_down(&self, action: Action) -> bool {
+        self.down.contains(&action)
+    }
+
+    /// The action is held this frame by the controls of `seat`.
+    pub fn is_down_for(&self, seat: Seat, action: Action) -> bool {
+        match seat {
+            Seat::Solo => self.is_down(action),
+            Seat::One => self.seats[0].contains(&action),
+            Seat::Two => self.seats[1].contains(&action),
+        }
+    }
+
+    /// The keys that move `seat`'s astronaut, e.g. "W/A/S/D", for prompts.
+    pub fn seat_keys(&self, seat: Seat) -> String {
+        let bound = match seat {
+            Seat::Solo => SEAT_ACTIONS,
+            Seat::One => SEAT_BINDINGS[0],
+            Seat::Two => SEAT_BINDINGS[1],
+        };
+        // Thrust, left, down, right: the order the keys usually sit in.
+        [bound[2], bound[0], bound[3], bound[1]]
+            .iter()
+            .map(|&action| self.bindings.short_label(action))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// The action started this frame.
+    pub fn is_pressed(&self, action: Action) -> bool {
+        self.down.contains(&action) && !self.previous.contains(&action)
+    }
+
+    /// Where a tap or click started this frame, if anywhere.
+    pub fn tap(&self) -> Option<Vec2> {
+        self.taps.first().copied()
+    }
+
+    /// A tap or click started inside `rect` this frame.
+    pub fn tapped(&self, rect: Rect) -> bool {
+        self.taps.iter().any(|&p| rect.contains(p))
+    }
+
+    /// A finger (or the mouse button) is held inside `rect`.
+    pub fn touching(&self, rect: Rect) -> bool {
+        self.pointers.iter().any(|&p| rect.contains(p))
+    }
+}
+
+#[cfg(feature = "gamepad")]
+mod gamepad {
+    use super::Action;
+    use gilrs::{Axis, Button, Gilrs};
+    use std::collections::HashSet;
+
+    // Stick values below this are treated as centred.
+    const DEADZONE: f32 = 0.4;
+
+    /// Adds the actions held on any connected gamepad. The first two pads
+    /// also drive the seats of a two-player run.
+    pub fn poll(gilrs: &mut Gilrs, down: &mut HashSet<Action>, seats: &mut [HashSet<Action>; 2]) {
+        // Drain events so gilrs keeps its cached button state current.
+        while gilrs.next_event().is_some() {}
+
+        for (i, (_id, pad)) in gilrs.gamepads().enumerate() {
+            let stick_x = pad.value(Axis::LeftStickX);
+            let stick_y = pad.value(Axis::LeftStickY);
+            let left = pad.is_pressed(Button::DPadLeft) || stick_x < -DEADZONE;
+            let right = pad.is_pressed(Button::DPadRight) || stick_x > DEADZONE;
+            let up = pad.is_pressed(Button::DPadUp) || stick_y > DEADZONE;
+            let down_pressed = pad.is_pressed(Button::DPadDown) || stick_y < -DEADZONE;
+            let thrust = up
+                || pad.is_pressed(Button::South)
+                || pad.is_pressed(Button::RightTrigger)
+                || pad.is_pressed(Button::RightTrigger2);
+
+            let held = [
+                (Action::MoveLeft, left),
+                (Action::MoveRight, right),
+                (Action::Up, up),
+                (Action::Down, down_pressed),
+                (Action::Thrust, thrust),
+                (Action::Confirm, pad.is_pressed(Button::South)),
+                (Action::Back, pad.is_pressed(Button::East)),
+                (Action::Pause, pad.is_pressed(Button::Start)),
+            ];
+            for (action, is_held) in held {
+                if is_held {
+                    down.insert(action);
+                    if let Some(seat) = seats.get_mut(i) {
+                        if super::SEAT_ACTIONS.contains(&action) {
+                            seat.insert(action);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
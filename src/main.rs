@@ -1,4 +1,53 @@
 use macroquad::prelude::*;
+use std::collections::HashMap;
+
+mod animation;
+mod assets;
+mod audio;
+mod bindings;
+mod boss;
+mod campaign;
+mod controls_screen;
+mod environment;
+mod hints;
+mod input;
+mod manipulatives;
+mod obstacles;
+mod onscreen_keyboard;
+mod particles;
+mod pause_menu;
+mod physics;
+mod powerups;
+mod progress;
+mod settings;
+mod settings_screen;
+mod storage;
+mod theme;
+mod touch;
+mod versus;
+mod view;
+
+use animation::Animation;
+use assets::{Loader, Textures};
+use audio::{Audio, Music, Sfx};
+use bindings::Bindings;
+use boss::Boss;
+use campaign::{MissionRun, MISSIONS};
+use controls_screen::{ControlsEvent, ControlsScreen};
+use environment::Environment;
+use hints::Hints;
+use input::{Action, Input, Seat};
+use obstacles::Obstacles;
+use onscreen_keyboard::{KeyboardEvent, OnScreenKeyboard};
+use particles::Particles;
+use pause_menu::{PauseEvent, PauseMenu};
+use physics::Physics;
+use powerups::{PowerUpKind, PowerUps};
+use progress::Progress;
+use settings::{AnswerReveal, Settings};
+use settings_screen::{SettingsEvent, SettingsScreen};
+use touch::{TouchControls, TouchScheme};
+use versus::Rival;
 
 // Rename the external rand crate to avoid collisions with macroquad's built-in rand.
 use ::rand as ext_rand;
@@ -8,10 +57,28 @@ use ext_rand::seq::SliceRandom;
 #[derive(Debug)]
 enum GameState {
     Menu,
-    NameEntry(Operation, i32), // <--- New variant to collect the player's name
+    NameEntry(GameMode), // <--- New variant to collect the player's name
+    StarMap(usize),      // Campaign mission selection, holding the highlighted mission.
     Playing,
-    Pause(f32),
+    Freeze(f32), // Short hold after a correct answer before the next question.
+    // The right answer shown after a mistake: seconds left, or None to wait for Confirm.
+    Reveal(Option<f32>),
+    Paused,              // Pause menu over the frozen run.
+    Resuming(f32),       // Countdown back into the run after the pause menu.
+    MissionComplete(u8), // Results of the mission just flown, with the stars earned.
     GameOver,
+    // The settings and key binding screens; true when opened from the pause menu.
+    Settings(bool),
+    Controls(bool),
+}
+
+// What the player chose from the menu: an endless run or the campaign.
+#[derive(Clone, Copy, Debug)]
+enum GameMode {
+    Endless(Operation, i32),
+    Campaign,
+    Versus(Operation), // Two players, each with their own name.
+    Coop(Operation),   // Two players on one team, with a team name.
 }
 
 #[derive(PartialEq, Debug)]
@@ -21,7 +88,7 @@ enum PlayerState {
 }
 
 // New enum for math operations.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 enum Operation {
     Addition,
     Subtraction, // <-- NEW
@@ -30,6 +97,97 @@ enum Operation {
     Mixed,
 }
 
+impl Operation {
+    fn name(self) -> &'static str {
+        match self {
+            Operation::Addition => "Addition",
+            Operation::Subtraction => "Subtraction",
+            Operation::Multiplication => "Multiplication",
+            Operation::Division => "Division",
+            Operation::Mixed => "Mixed",
+        }
+    }
+
+    // Order used when cycling through operations on the menu.
+    const CYCLE: [Operation; 5] = [
+        Operation::Addition,
+        Operation::Subtraction,
+        Operation::Multiplication,
+        Operation::Division,
+        Operation::Mixed,
+    ];
+
+    fn next(self) -> Operation {
+        let i = Self::CYCLE.iter().position(|&op| op == self).unwrap();
+        Self::CYCLE[(i + 1) % Self::CYCLE.len()]
+    }
+
+    fn previous(self) -> Operation {
+        let i = Self::CYCLE.iter().position(|&op| op == self).unwrap();
+        Self::CYCLE[(i + Self::CYCLE.len() - 1) % Self::CYCLE.len()]
+    }
+}
+
+/// The numbers behind a single question, e.g. 7 × 8.
+/// For division `a` is the dividend and `b` the divisor.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+struct Fact {
+    op: Operation,
+    a: i32,
+    b: i32,
+}
+
+impl Fact {
+    fn new(op: Operation, a: i32, b: i32) -> Fact {
+        Fact { op, a, b }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self.op {
+            Operation::Addition => "+",
+            Operation::Subtraction => "-",
+            Operation::Multiplication => "×",
+            Operation::Division => "÷",
+            Operation::Mixed => unreachable!("facts always use a single operation"),
+        }
+    }
+
+    fn answer(self) -> i32 {
+        match self.op {
+            Operation::Addition => self.a + self.b,
+            Operation::Subtraction => self.a - self.b,
+            Operation::Multiplication => self.a * self.b,
+            Operation::Division => self.a / self.b,
+            Operation::Mixed => unreachable!("facts always use a single operation"),
+        }
+    }
+
+    fn question_text(self) -> String {
+        format!("{} {} {} = ?", self.a, self.symbol(), self.b)
+    }
+
+    /// The worked answer, e.g. "7 × 8 = 56".
+    fn solution_text(self) -> String {
+        format!(
+            "{} {} {} = {}",
+            self.a,
+            self.symbol(),
+            self.b,
+            self.answer()
+        )
+    }
+}
+
+// Counts how often each fact was answered wrongly (or missed) this session.
+type Struggles = HashMap<Fact, u32>;
+
+struct Question {
+    fact: Fact,
+    text: String,
+    choices: Vec<MultipleChoice>,
+    obstacles: Obstacles,
+}
+
 struct MultipleChoice {
     x: f32,
     y: f32,
@@ -45,6 +203,10 @@ struct Player {
     width: f32,
     height: f32,
     state: PlayerState,
+    thrusting: bool, // Jetpack firing this frame; drives the flame drawing.
+    anim: Animation,
+    seat: Seat,
+    aboard: bool, // Co-op: reached the right shuttle, waiting for the teammate.
 }
 
 struct Alien {
@@ -53,12 +215,27 @@ struct Alien {
     width: f32,
     height: f32,
     speed: f32, // pixels per second
+    anim: Animation,
 }
 
+// Size of the virtual screen everything is laid out on; `view` scales it
+// to fit the real window.
+const SCREEN_WIDTH: f32 = 1024.0;
+const SCREEN_HEIGHT: f32 = 768.0;
+
 // Movement and layout constants.
-const MOVE_SPEED: f32 = 3.0;
-const BOOST: f32 = 0.3;
-const GRAVITY: f32 = 0.2;
+// Tumbling astronauts always drop back to the ground, even in zero-g.
+const FAIL_MIN_GRAVITY: f32 = 0.15;
+// Player two of a two-player run starts this far right of player one, and
+// is tinted so the two astronauts can be told apart.
+const SECOND_START_OFFSET: f32 = 70.0;
+const PLAYER_TWO_TINT: Color = Color::new(1.0, 0.7, 0.45, 1.0);
+
+// Jetpack fuel, when it's turned on. A full tank is 1.0; rates are per frame.
+const FUEL_DRAIN: f32 = 0.006; // About three seconds of thrust.
+const FUEL_REFILL: f32 = 0.01; // While standing on the ground.
+const CORRECT_FUEL_BONUS: f32 = 0.5;
+const FUEL_GAUGE_WIDTH: f32 = 120.0;
 const GROUND_Y: f32 = 600.0; // New top edge of the ground area.
 const GROUND_HEIGHT: f32 = 150.0; // New ground height.
 
@@ -67,89 +244,231 @@ const ALIEN_WALL_BUFFER: f32 = 10.0;
 const ALIEN_WIDTH: f32 = 60.0;
 const ALIEN_WALL: f32 = ALIEN_WIDTH + ALIEN_WALL_BUFFER; // 70
 
-// Lives: starting number and life-box dimensions.
+// How far above the ground the alien switches to its landing animation.
+const ALIEN_LAND_DISTANCE: f32 = 80.0;
+
+// Lives: default starting number and life-box dimensions.
 const INITIAL_LIVES: i32 = 10;
 const LIFE_BOX_SIZE: f32 = 20.0;
 const LIFE_BOX_SPACING: f32 = 5.0;
+// Seconds the right answer is shown for with `AnswerReveal::Brief`.
+const REVEAL_TIME: f32 = 2.5;
+
+// Helper function to draw centered text, scaled by the player's text size.
+// Side margin centered text stays inside.
+const TEXT_MARGIN: f32 = 20.0;
 
-// Helper function to draw centered text.
 fn draw_centered_text(text: &str, y: f32, font_size: u16, color: Color) {
-    let dims = measure_text(text, None, font_size, 1.0);
-    let x = (screen_width() - dims.width) / 2.0;
-    draw_text(text, x, y, font_size as f32, color);
+    let mut font_size = theme::scaled(font_size);
+    let mut dims = theme::measure_text(text, font_size);
+    // Text too wide for the screen at this scale is shrunk to fit.
+    let max_width = SCREEN_WIDTH - 2.0 * TEXT_MARGIN;
+    if dims.width > max_width {
+        font_size = (font_size as f32 * max_width / dims.width) as u16;
+        dims = theme::measure_text(text, font_size);
+    }
+    let x = (SCREEN_WIDTH - dims.width) / 2.0;
+    theme::draw_text(text, x, y, font_size, color);
+}
+
+// What picking a menu entry leads to.
+#[derive(Clone, Copy)]
+enum MenuEntry {
+    Endless(i32), // Starting score for the difficulty.
+    Campaign,
+    Versus,
+    Coop,
+    Settings,
+}
+
+// Menu entries with the action that picks them directly, if any.
+const MENU_ITEMS: [(&str, MenuEntry, Option<Action>); 8] = [
+    ("Easy", MenuEntry::Endless(0), Some(Action::Easy)),
+    ("Medium", MenuEntry::Endless(500), Some(Action::Medium)),
+    ("Hard", MenuEntry::Endless(1000), Some(Action::Hard)),
+    (
+        "Very Hard",
+        MenuEntry::Endless(1500),
+        Some(Action::VeryHard),
+    ),
+    ("Campaign", MenuEntry::Campaign, Some(Action::Campaign)),
+    ("Two players: Versus", MenuEntry::Versus, None),
+    ("Two players: Co-op", MenuEntry::Coop, None),
+    ("Settings", MenuEntry::Settings, None),
+];
+
+// Baselines of the menu lines that can be tapped.
+// Where the menu's lines go. The gaps grow with the text scale, and the
+// entries scroll when they no longer all fit.
+struct MenuLayout {
+    title_y: f32,
+    operation_y: f32,
+    operation_help_y: f32,
+    heading_y: f32,
+    first_item_y: f32,
+    item_spacing: f32,
+    first_item: usize, // The entry shown at the top of the list.
+    visible_items: usize,
+    prompt_y: f32,
+    touch_y: f32,
+}
+
+fn menu_layout(selected_item: usize) -> MenuLayout {
+    let scale = theme::text_scale();
+    let extra = scale - 1.0;
+    let title_y = SCREEN_HEIGHT / 2.0 - 220.0 - 74.0 * extra;
+    let operation_y = title_y + 80.0 + 30.0 * extra;
+    let operation_help_y = operation_y + 35.0 + 25.0 * extra;
+    let heading_y = operation_help_y + 50.0 + 20.0 * extra;
+    let first_item_y = heading_y + 45.0 + 40.0 * extra;
+    let item_spacing = 36.0 * scale;
+    let touch_y = SCREEN_HEIGHT - 54.0;
+    let prompt_y = touch_y - 40.0 * scale;
+    let room = ((prompt_y - 30.0 * scale - first_item_y) / item_spacing) as usize + 1;
+    let visible_items = room.clamp(1, MENU_ITEMS.len());
+    // Keeps the highlighted entry in the middle of the list where it can.
+    let first_item = selected_item
+        .saturating_sub(visible_items / 2)
+        .min(MENU_ITEMS.len() - visible_items);
+    MenuLayout {
+        title_y,
+        operation_y,
+        operation_help_y,
+        heading_y,
+        first_item_y,
+        item_spacing,
+        first_item,
+        visible_items,
+        prompt_y,
+        touch_y,
+    }
+}
+
+impl MenuLayout {
+    // The entries on screen, with the baseline of each.
+    fn items(&self) -> impl Iterator<Item = (usize, f32)> + '_ {
+        (self.first_item..self.first_item + self.visible_items)
+            .enumerate()
+            .map(|(slot, i)| (i, self.first_item_y + slot as f32 * self.item_spacing))
+    }
+}
+
+// Tappable area around a line of centered text with its baseline at `y`.
+fn text_line_rect(y: f32, width: f32) -> Rect {
+    let scale = theme::text_scale();
+    Rect::new(
+        (SCREEN_WIDTH - width) / 2.0,
+        y - 38.0 * scale,
+        width,
+        48.0 * scale,
+    )
 }
 
 // Draws the menu screen.
-fn draw_menu(selected_op: Operation) {
-    clear_background(SKYBLUE);
-    draw_centered_text("Math Game", screen_height() / 2.0 - 150.0, 60, BLACK);
+fn draw_menu(
+    selected_op: Operation,
+    selected_item: usize,
+    touch_scheme: TouchScheme,
+    bindings: &Bindings,
+) {
+    let palette = theme::palette();
+    let layout = menu_layout(selected_item);
+    clear_background(palette.background);
+    draw_centered_text("Math Game", layout.title_y, 60, palette.text);
+
+    // Display the currently selected operation.
     draw_centered_text(
-        "Select Difficulty Level:",
-        screen_height() / 2.0 - 50.0,
+        &format!("Operation:  < {} >", selected_op.name()),
+        layout.operation_y,
         40,
-        BLACK,
+        palette.text,
     );
+    let op_keys: Vec<String> = [
+        Action::Addition,
+        Action::Subtraction,
+        Action::Multiplication,
+        Action::Division,
+        Action::Mixed,
+    ]
+    .iter()
+    .map(|&action| bindings.short_label(action))
+    .collect();
     draw_centered_text(
-        "0: Easy    1: Medium    2: Hard    3: Very Hard",
-        screen_height() / 2.0,
-        40,
-        BLACK,
+        &format!("Left/Right to change, or press {}", op_keys.join(", ")),
+        layout.operation_help_y,
+        24,
+        palette.muted,
     );
+
     draw_centered_text(
-        "Press the corresponding number key to start",
-        screen_height() / 2.0 + 50.0,
-        30,
-        DARKGRAY,
+        "Select Difficulty Level:",
+        layout.heading_y,
+        40,
+        palette.text,
     );
-
-    // Display the currently selected operation.
-    let op_text = match selected_op {
-        Operation::Addition => {
-            "Operation: Addition (Press S for Subtraction, M for Multiplication, D for Division, X for Mixed)"
-        }
-        Operation::Subtraction => {
-            "Operation: Subtraction (Press A for Addition, M for Multiplication, D for Division, X for Mixed)"
-        }
-        Operation::Multiplication => {
-            "Operation: Multiplication (Press A for Addition, S for Subtraction, D for Division, X for Mixed)"
-        }
-        Operation::Division => {
-            "Operation: Division (Press A for Addition, S for Subtraction, M for Multiplication, X for Mixed)"
-        }
-        Operation::Mixed => {
-            "Operation: Mixed (Press A, S, M, or D for single ops)"
+    for (i, y) in layout.items() {
+        let (name, _, shortcut) = MENU_ITEMS[i];
+        let label = match shortcut {
+            Some(action) => format!("{}: {}", bindings.short_label(action), name),
+            None => name.to_string(),
+        };
+        if i == selected_item {
+            draw_centered_text(&format!("> {} <", label), y, 40, palette.text);
+        } else {
+            draw_centered_text(&label, y, 36, palette.muted);
         }
-    };
-    draw_centered_text(op_text, screen_height() / 2.0 + 100.0, 24, DARKGRAY);
+    }
+    draw_centered_text(
+        "Press a key, or Up/Down and Enter",
+        layout.prompt_y,
+        28,
+        palette.muted,
+    );
+    draw_centered_text(
+        &format!("Touch controls: {} (tap to change)", touch_scheme.name()),
+        layout.touch_y,
+        28,
+        palette.text,
+    );
 }
 
 // Configure the game window.
 fn conf() -> Conf {
+    let settings = Settings::load();
     Conf {
         window_title: "Math Game".to_owned(),
-        window_width: 1024,
-        window_height: 768,
+        window_width: settings.window_width,
+        window_height: settings.window_height,
+        fullscreen: settings.fullscreen,
         ..Default::default()
     }
 }
 
-// Create a fresh player starting at x = ALIEN_WALL, on the ground.
-fn new_player() -> Player {
+// Create a fresh player starting at x = ALIEN_WALL, on the ground. Player
+// two of a two-player run starts a little further right.
+fn new_player(seat: Seat) -> Player {
     Player {
-        x: ALIEN_WALL,
+        x: match seat {
+            Seat::Two => ALIEN_WALL + SECOND_START_OFFSET,
+            Seat::Solo | Seat::One => ALIEN_WALL,
+        },
         y: GROUND_Y - 50.0,
         vx: 0.0,
         vy: 0.0,
         width: 60.0,
         height: 60.0,
         state: PlayerState::Normal,
+        thrusting: false,
+        anim: Animation::new("idle"),
+        seat,
+        aboard: false,
     }
 }
 
 /// Generates a new math question and four multiple-choice answers.
 /// The behavior now depends on the chosen operation.
-fn generate_question(score: i32, op: Operation) -> (String, Vec<MultipleChoice>) {
-    let mut rng = ext_rand::thread_rng();
+fn generate_question(score: i32, op: Operation) -> Question {
+    let mut rng = ext_rand::rng();
 
     // 1. If we are in Mixed mode, randomly pick one of the other ops
     let actual_op = match op {
@@ -174,31 +493,31 @@ fn generate_question(score: i32, op: Operation) -> (String, Vec<MultipleChoice>)
     // or just reuse `addition_max`.
     let subtraction_max = addition_max;
 
-    // 3. Generate question & correct answer
-    let (question_str, correct_answer) = match actual_op {
+    // 3. Generate the fact (operands) for the question
+    let fact = match actual_op {
         Operation::Addition => {
-            let num1 = rng.gen_range(1..=addition_max);
-            let num2 = rng.gen_range(1..=addition_max);
-            (format!("{} + {} = ?", num1, num2), num1 + num2)
+            let num1 = rng.random_range(1..=addition_max);
+            let num2 = rng.random_range(1..=addition_max);
+            Fact::new(actual_op, num1, num2)
         }
         Operation::Subtraction => {
             // generate two random numbers, then subtract smaller from bigger
-            let num1 = rng.gen_range(1..=subtraction_max);
-            let num2 = rng.gen_range(1..=subtraction_max);
+            let num1 = rng.random_range(1..=subtraction_max);
+            let num2 = rng.random_range(1..=subtraction_max);
             let big = num1.max(num2);
             let small = num1.min(num2);
-            (format!("{} - {} = ?", big, small), big - small)
+            Fact::new(actual_op, big, small)
         }
         Operation::Multiplication => {
-            let num1 = rng.gen_range(1..=multiply_max);
-            let num2 = rng.gen_range(1..=multiply_max);
-            (format!("{} × {} = ?", num1, num2), num1 * num2)
+            let num1 = rng.random_range(1..=multiply_max);
+            let num2 = rng.random_range(1..=multiply_max);
+            Fact::new(actual_op, num1, num2)
         }
         Operation::Division => {
-            let divisor = rng.gen_range(1..=division_max);
-            let quotient = rng.gen_range(1..=division_max);
+            let divisor = rng.random_range(1..=division_max);
+            let quotient = rng.random_range(1..=division_max);
             let dividend = divisor * quotient;
-            (format!("{} ÷ {} = ?", dividend, divisor), quotient)
+            Fact::new(actual_op, dividend, divisor)
         }
         Operation::Mixed => unreachable!("Handled above"),
     };
@@ -213,6 +532,17 @@ fn generate_question(score: i32, op: Operation) -> (String, Vec<MultipleChoice>)
         Operation::Mixed => 1, // unreachable, but needed for completeness
     };
 
+    let mut question = question_for_fact(fact, ramp_max);
+    question.obstacles = Obstacles::generate(&question.choices, score);
+    question
+}
+
+/// Builds the four answer shuttles for a known fact.
+/// Wrong answers are drawn from `1..=ramp_max * 2`.
+fn question_for_fact(fact: Fact, ramp_max: i32) -> Question {
+    let mut rng = ext_rand::rng();
+    let correct_answer = fact.answer();
+
     // 4. Prepare the multiple choices (1 correct + 3 wrong)
     let mut answers: Vec<MultipleChoice> = Vec::new();
     // Correct
@@ -224,9 +554,9 @@ fn generate_question(score: i32, op: Operation) -> (String, Vec<MultipleChoice>)
     });
     // Wrong
     for _ in 0..3 {
-        let mut wrong = rng.gen_range(1..=(ramp_max * 2));
+        let mut wrong = rng.random_range(1..=(ramp_max * 2));
         while wrong == correct_answer {
-            wrong = rng.gen_range(1..=(ramp_max * 2));
+            wrong = rng.random_range(1..=(ramp_max * 2));
         }
         answers.push(MultipleChoice {
             x: 0.0,
@@ -241,7 +571,7 @@ fn generate_question(score: i32, op: Operation) -> (String, Vec<MultipleChoice>)
 
     // 5. Position the answer boxes across the screen
     let margin = 100.0;
-    let available_width = screen_width() - 2.0 * margin;
+    let available_width = SCREEN_WIDTH - 2.0 * margin;
     let num_choices = answers.len() as f32;
     let slot_width = available_width / num_choices;
     for (i, ans) in answers.iter_mut().enumerate() {
@@ -249,8 +579,103 @@ fn generate_question(score: i32, op: Operation) -> (String, Vec<MultipleChoice>)
         ans.y = 200.0;
     }
 
-    // 6. Return the question string & choices
-    (question_str, answers)
+    // 6. Return the question & choices
+    Question {
+        fact,
+        text: fact.question_text(),
+        choices: answers,
+        obstacles: Obstacles::none(),
+    }
+}
+
+/// Picks the next question: a boss question while a boss is being fought,
+/// otherwise one for the endless run or the active mission.
+fn next_question(
+    score: i32,
+    op: Operation,
+    mission: Option<&MissionRun>,
+    struggles: &Struggles,
+) -> Question {
+    match mission {
+        Some(run) if run.boss.is_some() => boss::boss_question(struggles, run.mission()),
+        Some(run) => {
+            let mission = run.mission();
+            generate_question(mission.difficulty, mission.pick_operation())
+        }
+        None => generate_question(score, op),
+    }
+}
+
+/// Moves on to the next question, bringing in the mission's boss once its
+/// regular questions have all been asked.
+fn advance_question(
+    score: i32,
+    op: Operation,
+    mission: &mut Option<MissionRun>,
+    struggles: &Struggles,
+    powerups: &PowerUps,
+) -> Question {
+    if let Some(run) = mission {
+        if run.needs_boss() {
+            run.start_boss();
+        }
+    }
+    let mut question = next_question(score, op, mission.as_ref(), struggles);
+    if powerups.is_active(PowerUpKind::FiftyFifty) {
+        powerups::remove_two_wrong(&mut question.choices);
+    }
+    question
+}
+
+// Who is flying a run.
+enum Lineup {
+    Solo,
+    Versus(String), // Player two's name.
+    Coop,
+}
+
+/// Resets everything a run keeps and starts one from `start_score`, or
+/// from the start of `new_mission`. Every way into a run goes through
+/// here, so nothing carries over from the last one.
+#[allow(clippy::too_many_arguments)]
+fn start_run(
+    lineup: Lineup,
+    op: Operation,
+    start_score: i32,
+    new_mission: Option<MissionRun>,
+    settings: &Settings,
+    struggles: &Struggles,
+    lives: &mut i32,
+    score: &mut i32,
+    question: &mut Question,
+    player: &mut Player,
+    player_two: &mut Option<Player>,
+    rival: &mut Option<Rival>,
+    mission: &mut Option<MissionRun>,
+    powerups: &mut PowerUps,
+    particles: &mut Particles,
+    hints: &mut Hints,
+    fuel: &mut f32,
+    touch: &mut TouchControls,
+    alien: &mut Alien,
+) {
+    *lives = settings.starting_lives;
+    *score = start_score;
+    *question = next_question(start_score, op, new_mission.as_ref(), struggles);
+    *mission = new_mission;
+    let solo = matches!(lineup, Lineup::Solo);
+    *player = new_player(if solo { Seat::Solo } else { Seat::One });
+    *player_two = (!solo).then(|| new_player(Seat::Two));
+    *rival = match lineup {
+        Lineup::Versus(name) => Some(Rival::new(name, settings.starting_lives)),
+        Lineup::Solo | Lineup::Coop => None,
+    };
+    *powerups = PowerUps::new();
+    particles.clear();
+    *hints = Hints::new();
+    *fuel = 1.0;
+    touch.clear_target();
+    alien.y = 0.0;
 }
 
 /// Updates the alien's speed based on the current score.
@@ -264,10 +689,17 @@ fn update_alien_speed(alien: &mut Alien, score: i32) {
     }
 }
 
+// Top edge of the on-screen keyboard on the name entry screen.
+fn name_keyboard_top() -> f32 {
+    SCREEN_HEIGHT / 2.0 - 110.0
+}
+
 /// Collect text input from the user.
 /// Pressing Backspace removes a character.
 /// Pressing Enter must be checked outside this function (in `NameEntry`).
-fn update_name_input(player_name: &mut String) {
+/// Returns true if anything was typed this frame.
+fn update_name_input(player_name: &mut String) -> bool {
+    let mut typed = false;
     // Read next character if any:
     if let Some(c) = get_char_pressed() {
         // We can filter out only 'printable' ASCII if we like, or keep it simple
@@ -275,276 +707,1012 @@ fn update_name_input(player_name: &mut String) {
         if c as u32 == 8 {
             // backspace
             player_name.pop();
-        } else if player_name.len() < 15 {
+            typed = true;
+        } else if !c.is_control() && player_name.len() < 15 {
             player_name.push(c);
+            typed = true;
         }
     }
 
     // Also handle if user presses BACKSPACE as a key (not captured by get_char_pressed)
     if is_key_pressed(KeyCode::Backspace) {
         player_name.pop();
+        typed = true;
     }
+    typed
 }
 
 #[macroquad::main(conf)]
 async fn main() {
+    let mut settings = Settings::load();
     let mut game_state = GameState::Menu;
     let mut player_name = String::new();
     let mut score = 0;
-    let mut lives = INITIAL_LIVES;
-    let mut question = String::new();
-    let mut choices: Vec<MultipleChoice> = Vec::new();
-    let mut player = new_player();
+    // Score the current run started from, for restarting it.
+    let mut start_score = 0;
+    let mut lives = settings.starting_lives;
+    let mut question = generate_question(score, Operation::Addition);
+    // Facts answered wrongly this session; boss questions are drawn from these.
+    let mut struggles: Struggles = HashMap::new();
+    let mut powerups = PowerUps::new();
+    let mut particles = Particles::new();
+    let mut hints = Hints::new();
+    // Kept across questions so a correct answer's bonus carries over.
+    let mut fuel = 1.0;
+    let mut player = new_player(Seat::Solo);
+    // Player two's astronaut in a two-player run. In versus they also have
+    // their own name, score, lives and fuel; a co-op team shares the run's.
+    let mut player_two: Option<Player> = None;
+    let mut rival: Option<Rival> = None;
+    let mut rival_name = String::new();
+    let mut naming_rival = false;
     let mut alien = Alien {
         x: 0.0,
         y: 0.0,
         width: 200.0,  // same as mathnaut
         height: 200.0, // same as mathnaut
         speed: 50.0,
+        anim: Animation::new("descend"),
     };
 
-    // Default operation set to Addition.
-    let mut selected_op = Operation::Addition;
+    let mut selected_op = settings.default_operation;
+    // Highlighted entry in MENU_ITEMS.
+    let mut menu_item = 0;
 
-    // Load textures.
-    let astronaut_texture = load_texture("assets/mathnaut.png").await.unwrap();
-    astronaut_texture.set_filter(FilterMode::Nearest);
+    let mut input = Input::new(Bindings::load());
+    let mut controls_screen = ControlsScreen::new();
+    let mut pause_menu = PauseMenu::new();
+    let mut name_keyboard = OnScreenKeyboard::new(name_keyboard_top());
+    let mut touch = TouchControls::new(settings.touch_scheme);
 
-    let flame_texture = load_texture("assets/flame.png").await.unwrap();
-    flame_texture.set_filter(FilterMode::Nearest);
+    // Campaign progress for every player, and the mission being flown (if any).
+    let mut progress = Progress::load();
+    let mut mission: Option<MissionRun> = None;
 
-    let shuttle_texture = load_texture("assets/shuttle.png").await.unwrap();
-    shuttle_texture.set_filter(FilterMode::Nearest);
+    // Load everything behind a loading screen. Missing sprites become
+    // placeholders and are reported on the menu.
+    theme::apply(&settings, None);
+    let mut loader = Loader::new(6);
+    let textures = Textures {
+        astronaut: loader.sheet("mathnaut.png", 128, WHITE).await,
+        flame: loader.sheet("flame.png", 64, ORANGE).await,
+        shuttle: loader.texture("shuttle.png", 512, LIGHTGRAY).await,
+        alien: loader.sheet("alien.png", 256, GREEN).await,
+    };
 
-    // Load the alien sprite.
-    let alien_texture = load_texture("assets/alien.png").await.unwrap();
-    alien_texture.set_filter(FilterMode::Nearest);
+    // The dyslexia-friendly font is optional; without it the default font is used.
+    loader.step("Loading fonts").await;
+    let dyslexic_font = match assets::load_bytes(theme::DYSLEXIC_FONT)
+        .await
+        .and_then(|bytes| load_ttf_font_from_bytes(&bytes))
+    {
+        Ok(font) => Some(font),
+        Err(err) => {
+            eprintln!("Dyslexia-friendly font unavailable: {}", err);
+            None
+        }
+    };
+    theme::apply(&settings, dyslexic_font.as_ref());
+    let mut settings_screen = SettingsScreen::new(dyslexic_font.is_some());
+    loader.step("Loading sounds").await;
+    let mut audio = Audio::load().await;
+    let asset_errors = loader.errors;
 
     loop {
-        // In the menu state, let the user change the operation type.
-        if let GameState::Menu = game_state {
-            // Change operation based on key press.
-            if is_key_pressed(KeyCode::A) {
-                selected_op = Operation::Addition;
-            } else if is_key_pressed(KeyCode::S) {
-                selected_op = Operation::Subtraction;
-            } else if is_key_pressed(KeyCode::M) {
-                selected_op = Operation::Multiplication;
-            } else if is_key_pressed(KeyCode::D) {
-                selected_op = Operation::Division;
-            } else if is_key_pressed(KeyCode::X) {
-                selected_op = Operation::Mixed;
-            }
+        input.update();
+        if input.is_pressed(Action::Fullscreen) {
+            settings.fullscreen = !settings.fullscreen;
+            set_fullscreen(settings.fullscreen);
+            settings.save();
+        }
+        // Effects keep moving everywhere except while the game is paused.
+        if !matches!(
+            game_state,
+            GameState::Paused
+                | GameState::Resuming(_)
+                | GameState::Settings(true)
+                | GameState::Controls(true)
+        ) {
+            particles.update(get_frame_time());
         }
+        view::begin_shaken(particles.shake_offset());
+        // Missions have a fixed environment; endless runs move on as the score climbs.
+        let environment = match &mission {
+            Some(run) => run.mission().environment,
+            None => Environment::for_score(versus::level(score, rival.as_ref())),
+        };
+        let in_run = matches!(
+            game_state,
+            GameState::Playing
+                | GameState::Freeze(_)
+                | GameState::Reveal(_)
+                | GameState::Paused
+                | GameState::Resuming(_)
+        );
+        theme::set_environment(in_run.then_some(environment));
 
         match game_state {
             GameState::Menu => {
-                draw_menu(selected_op);
-
                 // Operation selection
-                if is_key_pressed(KeyCode::A) {
+                if input.is_pressed(Action::Addition) {
                     selected_op = Operation::Addition;
-                } else if is_key_pressed(KeyCode::S) {
+                } else if input.is_pressed(Action::Subtraction) {
                     selected_op = Operation::Subtraction;
-                } else if is_key_pressed(KeyCode::M) {
+                } else if input.is_pressed(Action::Multiplication) {
                     selected_op = Operation::Multiplication;
-                } else if is_key_pressed(KeyCode::D) {
+                } else if input.is_pressed(Action::Division) {
                     selected_op = Operation::Division;
-                } else if is_key_pressed(KeyCode::X) {
+                } else if input.is_pressed(Action::Mixed) {
                     selected_op = Operation::Mixed;
+                } else if input.is_pressed(Action::MoveLeft) {
+                    selected_op = selected_op.previous();
+                } else if input.is_pressed(Action::MoveRight) {
+                    selected_op = selected_op.next();
+                } else if input.tapped(text_line_rect(menu_layout(menu_item).operation_y, 600.0)) {
+                    // Tapping the left half of the operation line goes back, the right half forward.
+                    match input.tap() {
+                        Some(tap) if tap.x < SCREEN_WIDTH / 2.0 => {
+                            selected_op = selected_op.previous()
+                        }
+                        _ => selected_op = selected_op.next(),
+                    }
                 }
+                if input.is_pressed(Action::Up) && menu_item > 0 {
+                    menu_item -= 1;
+                } else if input.is_pressed(Action::Down) && menu_item + 1 < MENU_ITEMS.len() {
+                    menu_item += 1;
+                }
+                if input.tapped(text_line_rect(menu_layout(menu_item).touch_y, 600.0)) {
+                    touch.scheme = touch.scheme.toggled();
+                    settings.touch_scheme = touch.scheme;
+                    settings.save();
+                }
+                draw_menu(selected_op, menu_item, touch.scheme, &input.bindings);
+                assets::draw_errors(&asset_errors);
 
-                // Difficulty selection
-                if is_key_pressed(KeyCode::Key0) {
-                    score = 0;
+                // Difficulty selection, by its key or by confirming the highlighted entry
+                let shortcut = MENU_ITEMS.iter().position(|&(_, _, shortcut)| {
+                    shortcut.is_some_and(|action| input.is_pressed(action))
+                });
+                let chosen = if shortcut.is_some() {
+                    shortcut
+                } else if input.is_pressed(Action::Confirm) {
+                    Some(menu_item)
+                } else {
+                    menu_layout(menu_item)
+                        .items()
+                        .find(|&(_, y)| input.tapped(text_line_rect(y, 400.0)))
+                        .map(|(i, _)| i)
+                };
+                if let Some(item) = chosen {
+                    menu_item = item;
                     // Instead of going directly to Playing, go to NameEntry
-                    game_state = GameState::NameEntry(selected_op, score);
+                    game_state = match MENU_ITEMS[item].1 {
+                        MenuEntry::Endless(start_score) => {
+                            score = start_score;
+                            GameState::NameEntry(GameMode::Endless(selected_op, score))
+                        }
+                        // The campaign needs a name first so progress can be saved per player.
+                        MenuEntry::Campaign => GameState::NameEntry(GameMode::Campaign),
+                        MenuEntry::Versus => {
+                            score = 0;
+                            GameState::NameEntry(GameMode::Versus(selected_op))
+                        }
+                        MenuEntry::Coop => {
+                            score = 0;
+                            GameState::NameEntry(GameMode::Coop(selected_op))
+                        }
+                        MenuEntry::Settings => GameState::Settings(false),
+                    };
                     player_name.clear(); // reset typed name
-                } else if is_key_pressed(KeyCode::Key1) {
-                    score = 500;
-                    game_state = GameState::NameEntry(selected_op, score);
-                    player_name.clear();
-                } else if is_key_pressed(KeyCode::Key2) {
-                    score = 1000;
-                    game_state = GameState::NameEntry(selected_op, score);
-                    player_name.clear();
-                } else if is_key_pressed(KeyCode::Key3) {
-                    score = 1500;
-                    game_state = GameState::NameEntry(selected_op, score);
-                    player_name.clear();
+                    rival_name.clear();
+                    naming_rival = false;
+                    name_keyboard = OnScreenKeyboard::new(name_keyboard_top());
                 }
             }
-            GameState::NameEntry(op, initial_score) => {
-                // 1) Let the user type characters for their name
-                update_name_input(&mut player_name);
+            GameState::NameEntry(mode) => {
+                // 1) Let the user type characters for their name, on a keyboard or on screen.
+                // A key that typed a letter doesn't also count as Confirm.
+                // A versus run asks for player two's name after player one's.
+                let name = if naming_rival {
+                    &mut rival_name
+                } else {
+                    &mut player_name
+                };
+                let typed = update_name_input(name);
+                // A typed letter can also be bound to a direction (W, A, S
+                // and D in the left-hand layouts), so the on-screen keyboard
+                // ignores that frame instead of showing its cursor again.
+                let keyboard_done = if typed {
+                    name_keyboard.disengage();
+                    false
+                } else {
+                    matches!(name_keyboard.update(&input, name), KeyboardEvent::Done)
+                };
 
                 // 2) Draw a “Name Entry” screen
-                clear_background(SKYBLUE);
+                let palette = theme::palette();
+                clear_background(palette.background);
+                // Each player's keys come from the controls screen.
+                let prompt = match mode {
+                    GameMode::Versus(_) if naming_rival => format!(
+                        "Player 2 ({}), enter your name:",
+                        input.seat_keys(Seat::Two)
+                    ),
+                    GameMode::Versus(_) => format!(
+                        "Player 1 ({}), enter your name:",
+                        input.seat_keys(Seat::One)
+                    ),
+                    GameMode::Coop(_) => format!(
+                        "Team name? (Player 1: {}, Player 2: {})",
+                        input.seat_keys(Seat::One),
+                        input.seat_keys(Seat::Two)
+                    ),
+                    _ => "Enter your name, then press [Enter]:".to_string(),
+                };
+                draw_centered_text(&prompt, SCREEN_HEIGHT / 2.0 - 250.0, 40, palette.text);
+                draw_centered_text(name, SCREEN_HEIGHT / 2.0 - 170.0, 50, palette.muted);
+                name_keyboard.draw();
                 draw_centered_text(
-                    "Enter your name, then press [Enter]:",
-                    screen_height() / 2.0 - 100.0,
-                    40,
-                    BLACK,
+                    "With a controller or touchscreen, use the keys above and pick OK",
+                    SCREEN_HEIGHT / 2.0 + 180.0,
+                    24,
+                    palette.muted,
                 );
-                draw_centered_text(&player_name, screen_height() / 2.0, 50, DARKGRAY);
-
-                // 3) If the user presses Enter and there's at least 1 char, move on to Playing
-                if is_key_pressed(KeyCode::Enter) && !player_name.is_empty() {
-                    // Initialize your playing conditions
-                    lives = INITIAL_LIVES;
-                    let (q, c) = generate_question(initial_score, op);
-                    // fill out question, choices, etc. and move to Playing
-                    score = initial_score;
-                    question = q;
-                    choices = c;
-                    player = new_player();
-                    alien.y = 0.0;
 
+                // 3) If the user confirms and there's at least 1 char, move on
+                let confirmed = keyboard_done
+                    || (!typed && !name_keyboard.is_engaged() && input.is_pressed(Action::Confirm));
+                if confirmed && !name.is_empty() {
+                    struggles.clear();
+                    match mode {
+                        GameMode::Endless(op, initial_score) => {
+                            start_score = initial_score;
+                            start_run(
+                                Lineup::Solo,
+                                op,
+                                start_score,
+                                None,
+                                &settings,
+                                &struggles,
+                                &mut lives,
+                                &mut score,
+                                &mut question,
+                                &mut player,
+                                &mut player_two,
+                                &mut rival,
+                                &mut mission,
+                                &mut powerups,
+                                &mut particles,
+                                &mut hints,
+                                &mut fuel,
+                                &mut touch,
+                                &mut alien,
+                            );
+                            game_state = GameState::Playing;
+                        }
+                        GameMode::Campaign => {
+                            // Start on the first mission this player hasn't passed yet.
+                            let next = (0..MISSIONS.len())
+                                .find(|&i| progress.stars(&player_name, i) == 0)
+                                .unwrap_or(0);
+                            game_state = GameState::StarMap(next);
+                        }
+                        GameMode::Versus(_) if !naming_rival => {
+                            naming_rival = true;
+                            name_keyboard = OnScreenKeyboard::new(name_keyboard_top());
+                        }
+                        GameMode::Versus(op) | GameMode::Coop(op) => {
+                            // A co-op team shares the usual lives, score and fuel.
+                            let lineup = match mode {
+                                GameMode::Versus(_) => Lineup::Versus(rival_name.clone()),
+                                _ => Lineup::Coop,
+                            };
+                            start_score = 0;
+                            start_run(
+                                lineup,
+                                op,
+                                start_score,
+                                None,
+                                &settings,
+                                &struggles,
+                                &mut lives,
+                                &mut score,
+                                &mut question,
+                                &mut player,
+                                &mut player_two,
+                                &mut rival,
+                                &mut mission,
+                                &mut powerups,
+                                &mut particles,
+                                &mut hints,
+                                &mut fuel,
+                                &mut touch,
+                                &mut alien,
+                            );
+                            game_state = GameState::Playing;
+                        }
+                    }
+                }
+            }
+            GameState::StarMap(ref mut selected) => {
+                // Tapping a mission selects it; tapping it again launches it.
+                let mut launch = input.is_pressed(Action::Confirm);
+                if let Some(tapped) = input.tap().and_then(campaign::mission_at) {
+                    launch = tapped == *selected;
+                    *selected = tapped;
+                }
+                if input.is_pressed(Action::MoveLeft) && *selected > 0 {
+                    *selected -= 1;
+                } else if input.is_pressed(Action::MoveRight) && *selected + 1 < MISSIONS.len() {
+                    *selected += 1;
+                }
+                let selected = *selected;
+                campaign::draw_star_map(&progress, &player_name, selected, &input.bindings);
+
+                if input.is_pressed(Action::Back) || input.tapped(campaign::back_button_rect()) {
+                    game_state = GameState::Menu;
+                } else if launch && progress.is_unlocked(&player_name, selected) {
+                    start_score = 0;
+                    start_run(
+                        Lineup::Solo,
+                        selected_op,
+                        start_score,
+                        Some(MissionRun::new(selected)),
+                        &settings,
+                        &struggles,
+                        &mut lives,
+                        &mut score,
+                        &mut question,
+                        &mut player,
+                        &mut player_two,
+                        &mut rival,
+                        &mut mission,
+                        &mut powerups,
+                        &mut particles,
+                        &mut hints,
+                        &mut fuel,
+                        &mut touch,
+                        &mut alien,
+                    );
                     game_state = GameState::Playing;
                 }
             }
             GameState::Playing => {
-                update_player(&mut player);
-                update_alien_speed(&mut alien, score);
-                alien.y += alien.speed * get_frame_time();
-                if alien.y + alien.height >= GROUND_Y {
-                    lives -= 1;
-                    if lives <= 0 {
-                        game_state = GameState::GameOver;
-                    } else {
-                        alien.y = 0.0;
-                        player = new_player();
-                        let (q, c) = generate_question(score, selected_op);
-                        question = q;
-                        choices = c;
+                // Touch controls are for one player only; hints are left out
+                // of versus runs.
+                let solo = player_two.is_none();
+                let coop = !solo && rival.is_none();
+                if solo {
+                    touch.update(&mut input, &player, &question.choices);
+                }
+                let hint_asked =
+                    input.is_pressed(Action::Hint) || input.tapped(hints::button_rect());
+                if (solo || coop) && hint_asked {
+                    // A tap on the hint button shouldn't become an autopilot target.
+                    touch.clear_target();
+                    if !hints.request(question.fact, &mut score) {
+                        audio.play(Sfx::Wrong, settings.sfx_volume);
                     }
                 }
-                if player.state == PlayerState::Normal {
-                    let mut collided = false;
-                    let mut correct_collision = false;
-                    for choice in &choices {
-                        if overlaps(
-                            player.x,
-                            player.y,
-                            player.width,
-                            player.height,
-                            choice.x,
-                            choice.y,
-                            100.0,
-                            80.0,
-                        ) {
-                            collided = true;
-                            correct_collision = choice.is_correct;
-                            break;
+                let physics = settings.physics.unwrap_or(environment.physics()).physics();
+                update_player(
+                    &mut player,
+                    &input,
+                    &physics,
+                    settings.fuel.then_some(&mut fuel),
+                );
+                if let Some(player_two) = &mut player_two {
+                    // Co-op teams share one tank.
+                    let tank = rival.as_mut().map_or(&mut fuel, |rival| &mut rival.fuel);
+                    update_player(player_two, &input, &physics, settings.fuel.then_some(tank));
+                }
+                question.obstacles.update(get_frame_time());
+                for astronaut in std::iter::once(&mut player).chain(player_two.as_mut()) {
+                    if astronaut.thrusting {
+                        particles.exhaust(exhaust_origin(astronaut), get_frame_time());
+                    }
+                    if astronaut.state == PlayerState::Normal && !astronaut.aboard {
+                        question.obstacles.support(astronaut);
+                        if question.obstacles.hits(astronaut) {
+                            // Hazards knock the astronaut down but don't cost a life.
+                            audio.play(Sfx::Wrong, settings.sfx_volume);
+                            particles.explosion(vec2(
+                                astronaut.x + astronaut.width / 2.0,
+                                astronaut.y + astronaut.height / 2.0,
+                            ));
+                            astronaut.state = PlayerState::Fail;
+                        }
+                    }
+                }
+                // Power-ups are left out of two-player runs, where only one player could use them.
+                let collected = if solo {
+                    powerups.update(get_frame_time(), &player)
+                } else {
+                    None
+                };
+                if let Some(kind) = collected {
+                    match kind {
+                        // Never takes away lives won above the cap elsewhere.
+                        PowerUpKind::ExtraLife => {
+                            lives = (lives + 1).min(settings.starting_lives.max(lives))
+                        }
+                        PowerUpKind::FiftyFifty => {
+                            powerups::remove_two_wrong(&mut question.choices)
+                        }
+                        PowerUpKind::Shield | PowerUpKind::SlowTime => {}
+                    }
+                }
+                let boss_fight = mission.as_ref().is_some_and(|run| run.boss.is_some());
+                // Either the boss or the regular alien reaching the ground costs a life.
+                let landed = match mission.as_mut().and_then(|run| run.boss.as_mut()) {
+                    Some(boss) => {
+                        boss.update(get_frame_time() * powerups.alien_speed_factor());
+                        boss.has_landed()
+                    }
+                    None => {
+                        match &mission {
+                            Some(run) => alien.speed = run.alien_speed(),
+                            None => {
+                                update_alien_speed(&mut alien, versus::level(score, rival.as_ref()))
+                            }
+                        }
+                        alien.speed *= powerups.alien_speed_factor();
+                        alien.y += alien.speed * get_frame_time();
+                        alien.anim.play(
+                            if alien.y + alien.height >= GROUND_Y - ALIEN_LAND_DISTANCE {
+                                "land"
+                            } else {
+                                "descend"
+                            },
+                        );
+                        alien.anim.update(get_frame_time());
+                        alien.y + alien.height >= GROUND_Y
+                    }
+                };
+                if landed {
+                    // In a versus run the alien costs both players a life.
+                    lives -= 1;
+                    if let Some(rival) = &mut rival {
+                        rival.lives -= 1;
+                    }
+                    particles.shake(particles::LIFE_LOST_SHAKE);
+                    audio.play(Sfx::AlienLanding, settings.sfx_volume);
+                    audio.play(Sfx::LifeLost, settings.sfx_volume);
+                    powerups.record_mistake();
+                    *struggles.entry(question.fact).or_insert(0) += 1;
+                    if let Some(run) = &mut mission {
+                        if boss_fight {
+                            // A landed boss counts as a wrong answer and climbs back up.
+                            run.record_wrong();
+                        } else {
+                            run.record_missed();
+                        }
+                        if let Some(boss) = &mut run.boss {
+                            boss.punish();
+                            boss.restart_descent();
+                        }
+                    }
+                    let rival_out = rival.as_ref().is_some_and(|rival| rival.lives <= 0);
+                    if lives <= 0
+                        || rival_out
+                        || mission.as_ref().is_some_and(|run| run.is_finished())
+                    {
+                        if lives <= 0 || rival_out {
+                            audio.play(Sfx::GameOver, settings.sfx_volume);
                         }
+                        game_state = end_run(mission.as_ref(), lives, &mut progress, &player_name);
+                    } else if let Some(reveal) = reveal_state(settings.answer_reveal) {
+                        game_state = reveal;
+                    } else if !boss_fight {
+                        alien.y = 0.0;
+                        player = new_player(player.seat);
+                        if let Some(player_two) = &mut player_two {
+                            *player_two = new_player(Seat::Two);
+                        }
+                        touch.clear_target();
+                        question = advance_question(
+                            versus::level(score, rival.as_ref()),
+                            selected_op,
+                            &mut mission,
+                            &struggles,
+                            &powerups,
+                        );
                     }
-                    if collided {
+                }
+                if player.state == PlayerState::Normal && !coop {
+                    if let Some((correct_collision, hit_at)) =
+                        shuttle_hit(&player, &question.choices)
+                    {
                         if correct_collision {
                             score += 100;
-                            game_state = GameState::Pause(0.5);
+                            fuel = f32::min(fuel + CORRECT_FUEL_BONUS, 1.0);
+                            audio.play(Sfx::Correct, settings.sfx_volume);
+                            particles.sparkles(hit_at);
+                            particles.float_text("+100", hit_at, theme::palette().good);
+                            if solo {
+                                powerups.record_correct();
+                            }
+                            let mut pause = 0.5;
+                            if let Some(run) = &mut mission {
+                                run.record_correct();
+                                if let Some(boss) = &mut run.boss {
+                                    boss.hit();
+                                }
+                                run.check_boss_defeated();
+                                if run.boss_defeated {
+                                    // Give the defeat animation time to play out.
+                                    pause = boss::DEFEATED_TIME;
+                                }
+                            }
+                            game_state = GameState::Freeze(pause);
                         } else {
-                            lives -= 1;
+                            powerups.record_mistake();
+                            audio.play(Sfx::Wrong, settings.sfx_volume);
+                            particles.explosion(hit_at);
+                            // A shield takes the hit instead of a life.
+                            if !powerups.absorb_hit() {
+                                lives -= 1;
+                                audio.play(Sfx::LifeLost, settings.sfx_volume);
+                                particles.shake(particles::LIFE_LOST_SHAKE);
+                            }
+                            *struggles.entry(question.fact).or_insert(0) += 1;
+                            if let Some(run) = &mut mission {
+                                run.record_wrong();
+                                if let Some(boss) = &mut run.boss {
+                                    boss.punish();
+                                }
+                            }
                             if lives <= 0 {
-                                game_state = GameState::GameOver;
+                                audio.play(Sfx::GameOver, settings.sfx_volume);
+                                game_state =
+                                    end_run(mission.as_ref(), lives, &mut progress, &player_name);
+                            } else if let Some(reveal) =
+                                reveal_state(settings.answer_reveal).filter(|_| solo)
+                            {
+                                if let Some(run) = &mut mission {
+                                    run.record_revealed();
+                                }
+                                game_state = reveal;
                             } else {
+                                // In a versus run only this player is stunned; the other plays on.
                                 player.state = PlayerState::Fail;
-                                println!("Wrong Answer!");
                             }
                         }
                     }
                 }
+                // A co-op team answers once both astronauts are aboard the right
+                // shuttle. A wrong shuttle costs a shared life and stuns only
+                // the astronaut that hit it.
+                if let (Some(player_two), true) = (&mut player_two, coop) {
+                    for astronaut in [&mut player, player_two] {
+                        if astronaut.state != PlayerState::Normal || astronaut.aboard {
+                            continue;
+                        }
+                        match shuttle_hit(astronaut, &question.choices) {
+                            Some((true, hit_at)) => {
+                                astronaut.aboard = true;
+                                audio.play(Sfx::Correct, settings.sfx_volume);
+                                particles.sparkles(hit_at);
+                            }
+                            Some((false, hit_at)) => {
+                                lives -= 1;
+                                audio.play(Sfx::Wrong, settings.sfx_volume);
+                                audio.play(Sfx::LifeLost, settings.sfx_volume);
+                                particles.explosion(hit_at);
+                                particles.shake(particles::LIFE_LOST_SHAKE);
+                                *struggles.entry(question.fact).or_insert(0) += 1;
+                                astronaut.state = PlayerState::Fail;
+                            }
+                            None => {}
+                        }
+                    }
+                    if lives <= 0 {
+                        audio.play(Sfx::GameOver, settings.sfx_volume);
+                        game_state = GameState::GameOver;
+                    } else if player.aboard && player_two.aboard {
+                        score += 100;
+                        fuel = f32::min(fuel + CORRECT_FUEL_BONUS, 1.0);
+                        if let Some(choice) = question.choices.iter().find(|c| c.is_correct) {
+                            let at = vec2(choice.x + 90.0, choice.y + 40.0);
+                            particles.float_text("+100", at, theme::palette().good);
+                        }
+                        game_state = GameState::Freeze(0.5);
+                    }
+                }
+                // Player two's shuttle hits in versus, unless player one already answered.
+                if let (Some(player_two), Some(rival)) = (&mut player_two, &mut rival) {
+                    let answering = player_two.state == PlayerState::Normal
+                        && matches!(game_state, GameState::Playing);
+                    match shuttle_hit(player_two, &question.choices).filter(|_| answering) {
+                        Some((true, hit_at)) => {
+                            rival.score += 100;
+                            rival.fuel = f32::min(rival.fuel + CORRECT_FUEL_BONUS, 1.0);
+                            audio.play(Sfx::Correct, settings.sfx_volume);
+                            particles.sparkles(hit_at);
+                            particles.float_text("+100", hit_at, theme::palette().good);
+                            game_state = GameState::Freeze(0.5);
+                        }
+                        Some((false, hit_at)) => {
+                            rival.lives -= 1;
+                            audio.play(Sfx::Wrong, settings.sfx_volume);
+                            audio.play(Sfx::LifeLost, settings.sfx_volume);
+                            particles.explosion(hit_at);
+                            particles.shake(particles::LIFE_LOST_SHAKE);
+                            *struggles.entry(question.fact).or_insert(0) += 1;
+                            if rival.lives <= 0 {
+                                audio.play(Sfx::GameOver, settings.sfx_volume);
+                                game_state = GameState::GameOver;
+                            } else {
+                                player_two.state = PlayerState::Fail;
+                            }
+                        }
+                        None => {}
+                    }
+                }
                 render_scene(
                     &question,
-                    &choices,
                     &player,
                     score,
                     &alien,
+                    mission.as_ref().and_then(|run| run.boss.as_ref()),
+                    &powerups,
                     lives,
-                    &astronaut_texture,
-                    &flame_texture,
-                    &shuttle_texture,
-                    &alien_texture,
+                    &textures,
+                    &particles,
+                    settings.manipulatives,
+                    settings.fuel.then_some(fuel),
+                    &player_name,
+                    player_two.as_ref(),
+                    rival.as_ref(),
                 );
+                if solo || coop {
+                    hints.draw(question.fact);
+                    hints.draw_button(score);
+                }
+                if solo {
+                    touch.draw(&input);
+                }
+                pause_menu::draw_button();
             }
-            GameState::Pause(ref mut time_left) => {
+            GameState::Freeze(ref mut time_left) => {
                 *time_left -= get_frame_time();
+                // Keep the boss's hurt or defeat animation running.
+                if let Some(boss) = mission.as_mut().and_then(|run| run.boss.as_mut()) {
+                    boss.update(get_frame_time());
+                }
                 if *time_left <= 0.0 {
-                    player = new_player();
-                    alien.y = 0.0;
-                    let (q, c) = generate_question(score, selected_op);
-                    question = q;
-                    choices = c;
-                    game_state = GameState::Playing;
+                    touch.clear_target();
+                    if mission.as_ref().is_some_and(|run| run.is_finished()) {
+                        game_state = end_run(mission.as_ref(), lives, &mut progress, &player_name);
+                    } else {
+                        player = new_player(player.seat);
+                        if let Some(player_two) = &mut player_two {
+                            *player_two = new_player(Seat::Two);
+                        }
+                        alien.y = 0.0;
+                        question = advance_question(
+                            versus::level(score, rival.as_ref()),
+                            selected_op,
+                            &mut mission,
+                            &struggles,
+                            &powerups,
+                        );
+                        game_state = GameState::Playing;
+                    }
                 }
                 render_scene(
                     &question,
-                    &choices,
                     &player,
                     score,
                     &alien,
+                    mission.as_ref().and_then(|run| run.boss.as_ref()),
+                    &powerups,
                     lives,
-                    &astronaut_texture,
-                    &flame_texture,
-                    &shuttle_texture,
-                    &alien_texture,
+                    &textures,
+                    &particles,
+                    settings.manipulatives,
+                    settings.fuel.then_some(fuel),
+                    &player_name,
+                    player_two.as_ref(),
+                    rival.as_ref(),
                 );
+                pause_menu::draw_button();
             }
-            GameState::GameOver => {
-                clear_background(SKYBLUE);
-                let game_over_text = "GAME OVER";
-                let score_str = format!("Score: {}", score);
-                draw_text(
-                    game_over_text,
-                    screen_width() / 2.0 - 150.0,
-                    screen_height() / 2.0,
-                    60.0,
-                    RED,
+            GameState::Reveal(ref mut time_left) => {
+                // Confirm or a tap moves on early.
+                let mut done = input.is_pressed(Action::Confirm) || input.tap().is_some();
+                if let Some(time_left) = time_left {
+                    *time_left -= get_frame_time();
+                    done |= *time_left <= 0.0;
+                }
+                let waiting = time_left.is_none();
+                render_scene(
+                    &question,
+                    &player,
+                    score,
+                    &alien,
+                    mission.as_ref().and_then(|run| run.boss.as_ref()),
+                    &powerups,
+                    lives,
+                    &textures,
+                    &particles,
+                    settings.manipulatives,
+                    settings.fuel.then_some(fuel),
+                    &player_name,
+                    player_two.as_ref(),
+                    rival.as_ref(),
                 );
-                draw_text(
-                    &score_str,
-                    screen_width() / 2.0 - 100.0,
-                    screen_height() / 2.0 + 80.0,
-                    40.0,
-                    BLACK,
+                draw_reveal(&question, waiting.then_some(&input));
+                if done {
+                    // Move on the same way as after a correct answer.
+                    game_state = GameState::Freeze(0.0);
+                }
+            }
+            GameState::Paused => {
+                let event = pause_menu.update(&input);
+                render_scene(
+                    &question,
+                    &player,
+                    score,
+                    &alien,
+                    mission.as_ref().and_then(|run| run.boss.as_ref()),
+                    &powerups,
+                    lives,
+                    &textures,
+                    &particles,
+                    settings.manipulatives,
+                    settings.fuel.then_some(fuel),
+                    &player_name,
+                    player_two.as_ref(),
+                    rival.as_ref(),
                 );
-                draw_text(
-                    "Press SPACE to return to Menu",
-                    screen_width() / 2.0 - 200.0,
-                    screen_height() / 2.0 + 140.0,
-                    30.0,
-                    DARKGRAY,
+                pause_menu.draw();
+                match event {
+                    PauseEvent::None => {}
+                    PauseEvent::Resume => {
+                        game_state = GameState::Resuming(pause_menu::RESUME_COUNTDOWN)
+                    }
+                    PauseEvent::Restart => {
+                        // Start the same run or mission over from scratch.
+                        let lineup = match (&rival, &player_two) {
+                            (Some(rival), _) => Lineup::Versus(rival.name.clone()),
+                            (None, Some(_)) => Lineup::Coop,
+                            (None, None) => Lineup::Solo,
+                        };
+                        let new_mission = mission.as_ref().map(|run| MissionRun::new(run.index));
+                        start_run(
+                            lineup,
+                            selected_op,
+                            start_score,
+                            new_mission,
+                            &settings,
+                            &struggles,
+                            &mut lives,
+                            &mut score,
+                            &mut question,
+                            &mut player,
+                            &mut player_two,
+                            &mut rival,
+                            &mut mission,
+                            &mut powerups,
+                            &mut particles,
+                            &mut hints,
+                            &mut fuel,
+                            &mut touch,
+                            &mut alien,
+                        );
+                        pause_menu.open(None);
+                        game_state = GameState::Resuming(pause_menu::RESUME_COUNTDOWN);
+                    }
+                    PauseEvent::Settings => game_state = GameState::Settings(true),
+                    PauseEvent::Quit => {
+                        mission = None;
+                        game_state = GameState::Menu;
+                    }
+                }
+            }
+            GameState::Resuming(ref mut time_left) => {
+                *time_left -= get_frame_time();
+                let done = *time_left <= 0.0;
+                render_scene(
+                    &question,
+                    &player,
+                    score,
+                    &alien,
+                    mission.as_ref().and_then(|run| run.boss.as_ref()),
+                    &powerups,
+                    lives,
+                    &textures,
+                    &particles,
+                    settings.manipulatives,
+                    settings.fuel.then_some(fuel),
+                    &player_name,
+                    player_two.as_ref(),
+                    rival.as_ref(),
+                );
+                if done {
+                    game_state = pause_menu.resumed_state();
+                } else {
+                    pause_menu::draw_countdown(*time_left);
+                }
+            }
+            GameState::GameOver => {
+                let palette = theme::palette();
+                clear_background(palette.background);
+                match &rival {
+                    Some(rival) => versus::draw_results(&player_name, score, lives, rival),
+                    None => {
+                        draw_centered_text("GAME OVER", SCREEN_HEIGHT / 2.0, 60, palette.bad);
+                        let score_str = if player_two.is_some() {
+                            format!("Team {}: {}", player_name, score)
+                        } else {
+                            format!("Score: {}", score)
+                        };
+                        draw_centered_text(
+                            &score_str,
+                            SCREEN_HEIGHT / 2.0 + 80.0,
+                            40,
+                            palette.text,
+                        );
+                    }
+                }
+                draw_centered_text(
+                    &format!(
+                        "Press {} to return to Menu",
+                        input.bindings.short_label(Action::Confirm).to_uppercase()
+                    ),
+                    SCREEN_HEIGHT / 2.0 + 140.0,
+                    30,
+                    palette.muted,
                 );
-                if is_key_pressed(KeyCode::Space) {
+                if input.is_pressed(Action::Confirm) || input.tap().is_some() {
                     game_state = GameState::Menu;
                 }
             }
+            GameState::Settings(from_pause) => {
+                let event = settings_screen.update(&input, &mut settings);
+                theme::apply(&settings, dyslexic_font.as_ref());
+                audio.set_volumes(settings.music_volume, settings.sfx_volume);
+                settings_screen.draw(&settings);
+                match event {
+                    SettingsEvent::None => {}
+                    SettingsEvent::Controls => game_state = GameState::Controls(from_pause),
+                    SettingsEvent::Close => {
+                        game_state = if from_pause {
+                            GameState::Paused
+                        } else {
+                            selected_op = settings.default_operation;
+                            GameState::Menu
+                        };
+                    }
+                }
+            }
+            GameState::Controls(from_pause) => {
+                let event = controls_screen.update(&mut input);
+                controls_screen.draw(&input.bindings);
+                if let ControlsEvent::Close = event {
+                    game_state = GameState::Settings(from_pause);
+                }
+            }
+            GameState::MissionComplete(stars) => {
+                if let Some(run) = &mission {
+                    campaign::draw_mission_complete(run, stars, &input.bindings);
+                    if input.is_pressed(Action::Confirm) || input.tap().is_some() {
+                        // Move the cursor on to the next mission after a pass.
+                        let next = if stars > 0 {
+                            (run.index + 1).min(MISSIONS.len() - 1)
+                        } else {
+                            run.index
+                        };
+                        mission = None;
+                        game_state = GameState::StarMap(next);
+                    }
+                }
+            }
+        }
+
+        // Music follows the current screen; the jetpack only hums in flight.
+        let track = match game_state {
+            GameState::Playing
+            | GameState::Freeze(_)
+            | GameState::Reveal(_)
+            | GameState::Paused
+            | GameState::Resuming(_) => {
+                if mission.as_ref().is_some_and(|run| run.boss.is_some()) {
+                    Music::Boss
+                } else {
+                    Music::Playing
+                }
+            }
+            GameState::MissionComplete(_) | GameState::GameOver => Music::Results,
+            _ => Music::Menu,
+        };
+        audio.set_music(track, settings.music_volume);
+        let thrusting =
+            player.thrusting || player_two.as_ref().is_some_and(|player| player.thrusting);
+        audio.set_thrust(
+            matches!(game_state, GameState::Playing) && thrusting,
+            settings.sfx_volume,
+        );
+
+        // Pausing takes effect from the next frame, so the press that opens
+        // the menu isn't also read as closing it.
+        if input.is_pressed(Action::Pause) || input.tapped(pause_menu::button_rect()) {
+            let freeze = match game_state {
+                GameState::Playing => Some(None),
+                GameState::Freeze(time_left) => Some(Some(time_left)),
+                _ => None,
+            };
+            if let Some(freeze) = freeze {
+                // A tap on the pause button shouldn't become an autopilot target.
+                touch.clear_target();
+                pause_menu.open(freeze);
+                game_state = GameState::Paused;
+            }
         }
+        view::end();
         next_frame().await;
     }
 }
 
-fn update_player(player: &mut Player) {
+/// Ends the current run. A campaign mission saves its stars and shows the
+/// mission results; an endless run goes to the usual Game Over screen.
+fn end_run(
+    mission: Option<&MissionRun>,
+    lives: i32,
+    progress: &mut Progress,
+    player_name: &str,
+) -> GameState {
+    match mission {
+        Some(run) => {
+            // Running out of lives always fails the mission.
+            let stars = if lives > 0 { run.stars() } else { 0 };
+            progress.record(player_name, run.index, stars);
+            GameState::MissionComplete(stars)
+        }
+        None => GameState::GameOver,
+    }
+}
+
+/// Moves the astronaut. With `fuel`, thrust burns it and standing on the
+/// ground refills it; an empty tank can't thrust.
+fn update_player(player: &mut Player, input: &Input, physics: &Physics, fuel: Option<&mut f32>) {
+    player.thrusting = false;
+    if player.aboard {
+        return;
+    }
     match player.state {
         PlayerState::Normal => {
-            if is_key_down(KeyCode::Left) {
-                player.vx = -MOVE_SPEED;
-            } else if is_key_down(KeyCode::Right) {
-                player.vx = MOVE_SPEED;
+            let target_vx = if input.is_down_for(player.seat, Action::MoveLeft) {
+                -physics.move_speed
+            } else if input.is_down_for(player.seat, Action::MoveRight) {
+                physics.move_speed
             } else {
+                0.0
+            };
+            player.vx += (target_vx - player.vx) * physics.steering;
+            if player.vx.abs() < 0.05 {
                 player.vx = 0.0;
             }
-            if is_key_down(KeyCode::Up) {
-                player.vy -= BOOST;
+            let has_fuel = fuel.as_ref().is_none_or(|fuel| **fuel > 0.0);
+            if input.is_down_for(player.seat, Action::Thrust) && has_fuel {
+                player.vy -= physics.boost;
+                player.thrusting = true;
+            } else if physics.thrust_down && input.is_down_for(player.seat, Action::Down) {
+                player.vy += physics.boost;
             }
-            player.vy += GRAVITY;
+            player.vy += physics.gravity;
             player.x += player.vx;
             player.y += player.vy;
-            let screen_w = screen_width();
+            let screen_w = SCREEN_WIDTH;
             if player.x < ALIEN_WALL {
                 player.x = ALIEN_WALL;
+                player.vx = 0.0;
             }
             if player.x + player.width > screen_w {
                 player.x = screen_w - player.width;
+                player.vx = 0.0;
             }
             if player.y < 0.0 {
                 player.y = 0.0;
@@ -557,7 +1725,7 @@ fn update_player(player: &mut Player) {
         }
         PlayerState::Fail => {
             player.vx = 0.0;
-            player.vy += GRAVITY;
+            player.vy += physics.gravity.max(FAIL_MIN_GRAVITY);
             player.x += player.vx;
             player.y += player.vy;
             if player.y + player.height > GROUND_Y + player.height {
@@ -567,41 +1735,82 @@ fn update_player(player: &mut Player) {
             }
         }
     }
+    let on_ground = player.y >= GROUND_Y;
+    if let Some(fuel) = fuel {
+        if player.thrusting {
+            *fuel = (*fuel - FUEL_DRAIN).max(0.0);
+        } else if on_ground {
+            *fuel = (*fuel + FUEL_REFILL).min(1.0);
+        }
+    }
+    player.anim.play(match player.state {
+        PlayerState::Fail => "tumble",
+        PlayerState::Normal if player.thrusting => "thrust",
+        PlayerState::Normal if on_ground && player.vx != 0.0 => "walk",
+        PlayerState::Normal => "idle",
+    });
+    player.anim.update(get_frame_time());
 }
 
+#[allow(clippy::too_many_arguments)]
 fn render_scene(
-    question: &str,
-    choices: &[MultipleChoice],
+    question: &Question,
     player: &Player,
     score: i32,
     alien: &Alien,
+    boss: Option<&Boss>,
+    powerups: &PowerUps,
     lives: i32,
-    astronaut_texture: &Texture2D,
-    flame_texture: &Texture2D,
-    shuttle_texture: &Texture2D,
-    alien_texture: &Texture2D,
+    textures: &Textures,
+    particles: &Particles,
+    manipulatives: bool,
+    fuel: Option<f32>,
+    player_name: &str,
+    player_two: Option<&Player>,
+    rival: Option<&Rival>,
 ) {
-    clear_background(SKYBLUE);
+    let palette = theme::palette();
+    clear_background(palette.background);
+    let scenery = theme::scenery();
+    if let Some(environment) = scenery {
+        environment::draw_background(environment, player.x, GROUND_Y + player.height);
+    }
     // Draw the ground.
     draw_rectangle(
         0.0,
         GROUND_Y + player.height,
-        screen_width(),
+        SCREEN_WIDTH,
         GROUND_HEIGHT,
-        BROWN,
+        palette.ground,
     );
+    if let Some(environment) = scenery {
+        environment::draw_ground_tiles(environment, GROUND_Y + player.height, GROUND_HEIGHT);
+    }
     // Draw the question (centered).
-    draw_centered_text(question, 100.0, 50, BLACK);
-    // Draw the score at top-right.
-    let score_str = format!("Score: {}", score);
-    let score_dimensions = measure_text(&score_str, None, 40, 1.0);
-    let x_score = screen_width() - score_dimensions.width - 20.0;
-    draw_text(&score_str, x_score, 50.0, 40.0, BLACK);
+    draw_centered_text(&question.text, 100.0, 50, palette.text);
+    // Counting pictures go behind everything the player can touch.
+    if manipulatives {
+        manipulatives::draw(question.fact);
+    }
+    // Draw the score at top-right: both players' in a versus run, the
+    // team's in co-op.
+    let score_str = match (player_two, rival) {
+        (_, Some(rival)) => format!(
+            "{}: {}   {}: {}",
+            player_name, score, rival.name, rival.score
+        ),
+        (Some(_), None) => format!("Team {}: {}", player_name, score),
+        (None, None) => format!("Score: {}", score),
+    };
+    let score_size = theme::scaled(40);
+    let score_dimensions = theme::measure_text(&score_str, score_size);
+    let x_score = SCREEN_WIDTH - score_dimensions.width - 20.0;
+    theme::draw_text(&score_str, x_score, 50.0, score_size, palette.text);
     // Draw the answer boxes.
-    for choice in choices {
+    for choice in &question.choices {
         // Draw the shuttle sprite as the background for the answer box.
         draw_texture_ex(
-            shuttle_texture,
+            &textures.shuttle,
             choice.x - 10.0,
             choice.y,
             WHITE,
@@ -610,16 +1819,162 @@ fn render_scene(
                 ..Default::default()
             },
         );
-        // Draw the answer text on top of the shuttle sprite.
-        let text_x = choice.x + 75.0;
+        // Draw the answer text above the shuttle sprite, centred so larger
+        // text sizes still fit. It sits on the sky, so it takes the palette's
+        // text color, which each environment and theme sets to match.
+        let answer_size = theme::scaled(50);
+        let answer_width = theme::measure_text(&choice.text, answer_size).width;
+        let text_x = choice.x + 95.0 - answer_width / 2.0;
         let text_y = choice.y - 15.0;
-        draw_text(&choice.text, text_x, text_y, 50.0, BLACK);
+        theme::draw_text(&choice.text, text_x, text_y, answer_size, palette.text);
+    }
+    question.obstacles.draw();
+    powerups.draw_pickup();
+    draw_astronaut(player, textures, WHITE);
+    if let Some(player_two) = player_two {
+        draw_astronaut(player_two, textures, PLAYER_TWO_TINT);
+        // Versus players go by their names, co-op teammates by number.
+        let (name_one, name_two) = match rival {
+            Some(rival) => (player_name, rival.name.as_str()),
+            None => ("P1", "P2"),
+        };
+        draw_name_tag(player, name_one, palette.text);
+        draw_name_tag(player_two, name_two, PLAYER_TWO_TINT);
+    }
+    // A shield shows as a bubble around the astronaut.
+    if powerups.is_active(PowerUpKind::Shield) {
+        let center_x = player.x + player.width / 2.0;
+        let center_y = player.y + player.height / 2.0;
+        draw_circle(center_x, center_y, 55.0, Color::new(0.4, 0.8, 1.0, 0.25));
+        draw_circle_lines(center_x, center_y, 55.0, 2.0, SKYBLUE);
+    }
+    // Draw the boss with its health bar, or the regular alien sprite.
+    if let Some(boss) = boss {
+        boss.draw(&textures.alien);
+        let bar_width = 400.0;
+        let bar_x = (SCREEN_WIDTH - bar_width) / 2.0;
+        let bar_y = 115.0;
+        let health = boss.health as f32 / boss.max_health as f32;
+        // The bar grows with the text so the name still fits inside.
+        let name_size = theme::scaled(22);
+        let bar_height = name_size as f32;
+        draw_rectangle(bar_x, bar_y, bar_width, bar_height, palette.background);
+        draw_rectangle(bar_x, bar_y, bar_width * health, bar_height, palette.bad);
+        draw_rectangle_lines(bar_x, bar_y, bar_width, bar_height, 2.0, palette.text);
+        // The name sits on its own plate so it reads over any fill color.
+        let name_width = theme::measure_text(boss.name, name_size).width;
+        draw_rectangle(
+            bar_x + 4.0,
+            bar_y + 2.0,
+            name_width + 8.0,
+            bar_height - 4.0,
+            palette.background,
+        );
+        theme::draw_text(
+            boss.name,
+            bar_x + 8.0,
+            bar_y + bar_height * 0.77,
+            name_size,
+            palette.text,
+        );
+    } else {
+        textures.alien.draw(
+            &alien.anim,
+            alien.x,
+            alien.y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(Vec2::new(alien.width, alien.height)),
+                ..Default::default()
+            },
+        );
+    }
+    particles.draw();
+    // Draw lives inside the ground (bottom-left). A versus run has a row
+    // per player, labeled with their names.
+    let life_y = GROUND_Y + player.height + (GROUND_HEIGHT - LIFE_BOX_SIZE) / 2.0;
+    match rival {
+        Some(rival) => {
+            draw_life_row(player_name, lives, fuel, life_y - 35.0);
+            draw_life_row(&rival.name, rival.lives, fuel.map(|_| rival.fuel), life_y);
+        }
+        None => draw_life_row("Lives", lives, fuel, life_y),
     }
-    // If the up arrow is pressed, draw the flame behind the astronaut.
-    if is_key_down(KeyCode::Up) {
-        let flicker_scale: f32 = 0.8 + ext_rand::random::<f32>() * 0.5;
-        let flame_width = flame_texture.width() * flicker_scale;
-        let flame_height = flame_texture.height() * flicker_scale;
+    // Active power-ups sit at the other end of the ground.
+    powerups.draw_hud(life_y);
+}
+
+/// One row of the ground HUD: `label` and the life count as text, so it
+/// doesn't rely on color, then one small box per life and the fuel gauge.
+fn draw_life_row(label: &str, lives: i32, fuel: Option<f32>, y: f32) {
+    let palette = theme::palette();
+    let lives_str = format!("{}: {}", label, lives);
+    let lives_size = theme::scaled(30);
+    theme::draw_text(
+        &lives_str,
+        10.0,
+        y + LIFE_BOX_SIZE,
+        lives_size,
+        palette.ground_text,
+    );
+    let mut life_x = 20.0 + theme::measure_text(&lives_str, lives_size).width;
+    for _ in 0..lives {
+        draw_rectangle(life_x, y, LIFE_BOX_SIZE, LIFE_BOX_SIZE, palette.lives);
+        life_x += LIFE_BOX_SIZE + LIFE_BOX_SPACING;
+    }
+    // The fuel gauge follows the lives; it blinks once the tank is nearly empty.
+    if let Some(fuel) = fuel {
+        let label_x = life_x + 20.0;
+        let label_size = theme::scaled(30);
+        theme::draw_text(
+            "Fuel",
+            label_x,
+            y + LIFE_BOX_SIZE,
+            label_size,
+            palette.ground_text,
+        );
+        let gauge_x = label_x + theme::measure_text("Fuel", label_size).width + 10.0;
+        let low = fuel < 0.25;
+        let color = if low { palette.bad } else { palette.good };
+        if !low || (get_time() * 4.0) as i32 % 2 == 0 {
+            draw_rectangle(gauge_x, y, FUEL_GAUGE_WIDTH * fuel, LIFE_BOX_SIZE, color);
+        }
+        draw_rectangle_lines(
+            gauge_x,
+            y,
+            FUEL_GAUGE_WIDTH,
+            LIFE_BOX_SIZE,
+            2.0,
+            palette.ground_text,
+        );
+    }
+}
+
+/// Writes `name` above an astronaut.
+fn draw_name_tag(player: &Player, name: &str, color: Color) {
+    let size = theme::scaled(22);
+    let width = theme::measure_text(name, size).width;
+    theme::draw_text(
+        name,
+        player.x + (player.width - width) / 2.0,
+        player.y - 12.0,
+        size,
+        color,
+    );
+}
+
+/// Draws an astronaut, with the jetpack flame while it's firing.
+fn draw_astronaut(player: &Player, textures: &Textures, tint: Color) {
+    // If the jetpack is firing, draw the flame behind the astronaut.
+    if player.thrusting {
+        // An animated flame has its own flicker; a single frame gets a random scale.
+        let flicker_scale: f32 = if textures.flame.is_animated() {
+            1.0
+        } else {
+            0.8 + ext_rand::random::<f32>() * 0.5
+        };
+        let flame_size = textures.flame.frame_size() * flicker_scale;
+        let flame_height = flame_size.y;
 
         // Determine facing: assume when player.vx <= 0, astronaut faces right.
         let facing_right = player.vx <= 0.0;
@@ -632,13 +1987,14 @@ fn render_scene(
         let backpack_offset_x = player.x + offset_x;
         let backpack_offset_y = player.y + (player.height / 2.0) - (flame_height / 2.0) + offset_y;
 
-        draw_texture_ex(
-            flame_texture,
+        textures.flame.draw_frame(
+            "burn",
+            player.anim.time,
             backpack_offset_x,
             backpack_offset_y,
             WHITE,
             DrawTextureParams {
-                dest_size: Some(Vec2::new(flame_width, flame_height)),
+                dest_size: Some(flame_size),
                 rotation: 0.0,
                 flip_x: player.vx < 0.0,
                 flip_y: true,
@@ -648,11 +2004,11 @@ fn render_scene(
         );
     }
     // Draw the astronaut sprite.
-    draw_texture_ex(
-        astronaut_texture,
+    textures.astronaut.draw(
+        &player.anim,
         player.x,
         player.y,
-        WHITE,
+        tint,
         DrawTextureParams {
             dest_size: None,
             source: None,
@@ -662,26 +2018,92 @@ fn render_scene(
             pivot: None,
         },
     );
-    // Draw the alien sprite.
-    draw_texture_ex(
-        alien_texture,
-        alien.x,
-        alien.y,
-        WHITE,
-        DrawTextureParams {
-            dest_size: Some(Vec2::new(alien.width, alien.height)),
-            ..Default::default()
-        },
+}
+
+// The state that shows the right answer after a mistake, if the player wants it.
+fn reveal_state(reveal: AnswerReveal) -> Option<GameState> {
+    match reveal {
+        AnswerReveal::Off => None,
+        AnswerReveal::Brief => Some(GameState::Reveal(Some(REVEAL_TIME))),
+        AnswerReveal::UntilConfirmed => Some(GameState::Reveal(None)),
+    }
+}
+
+/// Highlights the correct shuttle and shows the worked answer under the
+/// question. With `waiting_for` set, also says which key moves on.
+fn draw_reveal(question: &Question, waiting_for: Option<&Input>) {
+    let palette = theme::palette();
+    let pulse = 4.0 + (get_time() as f32 * 6.0).sin().abs() * 4.0;
+    for choice in question.choices.iter().filter(|choice| choice.is_correct) {
+        draw_rectangle_lines(
+            choice.x - 20.0,
+            choice.y - 65.0,
+            220.0,
+            275.0,
+            pulse,
+            palette.good,
+        );
+    }
+    let solution = question.fact.solution_text();
+    let size = theme::scaled(50);
+    let width = theme::measure_text(&solution, size).width + 40.0;
+    let (panel_y, panel_h) = (150.0, size as f32 + 20.0);
+    draw_rectangle(
+        (SCREEN_WIDTH - width) / 2.0,
+        panel_y,
+        width,
+        panel_h,
+        palette.background,
     );
-    // Draw lives as small red boxes inside the ground (bottom-left).
-    let mut life_x = 10.0;
-    let life_y = GROUND_Y + player.height + (GROUND_HEIGHT - LIFE_BOX_SIZE) / 2.0;
-    for _ in 0..lives {
-        draw_rectangle(life_x, life_y, LIFE_BOX_SIZE, LIFE_BOX_SIZE, RED);
-        life_x += LIFE_BOX_SIZE + LIFE_BOX_SPACING;
+    draw_rectangle_lines(
+        (SCREEN_WIDTH - width) / 2.0,
+        panel_y,
+        width,
+        panel_h,
+        3.0,
+        palette.good,
+    );
+    draw_centered_text(&solution, panel_y + panel_h - 18.0, 50, palette.good);
+    if let Some(input) = waiting_for {
+        let prompt = format!(
+            "Press {} to continue",
+            input.bindings.short_label(Action::Confirm).to_uppercase()
+        );
+        draw_centered_text(&prompt, panel_y + panel_h + 35.0, 28, palette.text);
     }
 }
 
+// Where exhaust leaves the jetpack: the tip of the flame drawn in `render_scene`.
+fn exhaust_origin(player: &Player) -> Vec2 {
+    let x = if player.vx <= 0.0 {
+        player.x + 72.0
+    } else {
+        player.x + player.width + 12.0
+    };
+    vec2(x, player.y + player.height / 2.0 + 72.0)
+}
+
+/// The shuttle `player` is touching, if any: whether it holds the right
+/// answer, and its middle.
+fn shuttle_hit(player: &Player, choices: &[MultipleChoice]) -> Option<(bool, Vec2)> {
+    choices
+        .iter()
+        .find(|choice| {
+            overlaps(
+                player.x,
+                player.y,
+                player.width,
+                player.height,
+                choice.x,
+                choice.y,
+                100.0,
+                80.0,
+            )
+        })
+        .map(|choice| (choice.is_correct, vec2(choice.x + 90.0, choice.y + 40.0)))
+}
+
+#[allow(clippy::too_many_arguments)]
 fn overlaps(ax: f32, ay: f32, aw: f32, ah: f32, bx: f32, by: f32, bw: f32, bh: f32) -> bool {
     ax < bx + bw && ax + aw > bx && ay < by + bh && ay + ah > by
 }
@@ -0,0 +1,122 @@
+use macroquad::prelude::*;
+
+use crate::theme;
+use crate::{Fact, Operation, SCREEN_WIDTH};
+
+// Pictures are only drawn for numbers small enough to count.
+pub const MAX_DOTS_SIDE: i32 = 12;
+const MAX_TEN_FRAMES: i32 = 4;
+const MAX_GROUPS: i32 = 10;
+const MAX_GROUP_SIZE: i32 = 12;
+
+// Ten-frames: two rows of five cells.
+const FRAME_CELL: f32 = 28.0;
+const FRAME_GAP: f32 = 24.0;
+
+/// The strip under the shuttles the pictures are drawn in.
+fn area() -> Rect {
+    Rect::new((SCREEN_WIDTH - 700.0) / 2.0, 420.0, 700.0, 160.0)
+}
+
+/// Draws objects for the question's operands: ten-frames for addition and
+/// subtraction, a dot array for multiplication and equal groups for
+/// division. Nothing is drawn when the numbers are too big to count.
+pub fn draw(fact: Fact) {
+    let area = area();
+    match fact.op {
+        Operation::Addition => draw_ten_frames(fact.a, fact.b, false, area),
+        Operation::Subtraction => draw_ten_frames(fact.a, fact.b, true, area),
+        Operation::Multiplication => draw_dots(fact.a, fact.b, area),
+        Operation::Division => draw_groups(fact.a, fact.b, area),
+        Operation::Mixed => unreachable!("facts always use a single operation"),
+    }
+}
+
+/// Fills ten-frames with `a` counters and then `b` more in another color.
+/// When `take_away` is set, the last `b` of the `a` counters are crossed out
+/// instead.
+fn draw_ten_frames(a: i32, b: i32, take_away: bool, area: Rect) {
+    let total = if take_away { a } else { a + b };
+    if a < 0 || b < 0 || b > total || total > MAX_TEN_FRAMES * 10 {
+        return;
+    }
+    let palette = theme::palette();
+    let frames = (total.max(1) + 9) / 10;
+    let frame_width = FRAME_CELL * 5.0;
+    let width = frames as f32 * frame_width + (frames - 1) as f32 * FRAME_GAP;
+    let left = area.x + (area.w - width) / 2.0;
+    let top = area.y + (area.h - FRAME_CELL * 2.0) / 2.0;
+    for i in 0..frames * 10 {
+        let x = left + (i / 10) as f32 * (frame_width + FRAME_GAP) + (i % 5) as f32 * FRAME_CELL;
+        let y = top + (i % 10 / 5) as f32 * FRAME_CELL;
+        draw_rectangle_lines(x, y, FRAME_CELL, FRAME_CELL, 2.0, palette.text);
+        if i >= total {
+            continue;
+        }
+        let (cx, cy, r) = (
+            x + FRAME_CELL / 2.0,
+            y + FRAME_CELL / 2.0,
+            FRAME_CELL * 0.35,
+        );
+        if take_away {
+            draw_circle(cx, cy, r, palette.good);
+            if i >= a - b {
+                draw_line(cx - r, cy - r, cx + r, cy + r, 3.0, palette.bad);
+                draw_line(cx - r, cy + r, cx + r, cy - r, 3.0, palette.bad);
+            }
+        } else {
+            let color = if i < a { palette.good } else { palette.lives };
+            draw_circle(cx, cy, r, color);
+        }
+    }
+}
+
+/// An array of `rows` × `columns` dots, if it's small enough to count.
+pub fn draw_dots(rows: i32, columns: i32, area: Rect) {
+    if rows <= 0 || columns <= 0 || rows.max(columns) > MAX_DOTS_SIDE {
+        return;
+    }
+    let cell = (area.w / columns as f32)
+        .min(area.h / rows as f32)
+        .min(24.0);
+    let left = area.x + (area.w - cell * columns as f32) / 2.0;
+    let top = area.y + (area.h - cell * rows as f32) / 2.0;
+    let color = theme::palette().good;
+    for row in 0..rows {
+        for column in 0..columns {
+            let x = left + (column as f32 + 0.5) * cell;
+            let y = top + (row as f32 + 0.5) * cell;
+            draw_circle(x, y, cell * 0.35, color);
+        }
+    }
+}
+
+/// `total` items shared into `groups` equal circles.
+fn draw_groups(total: i32, groups: i32, area: Rect) {
+    if groups <= 0 || groups > MAX_GROUPS || total < 0 || total % groups != 0 {
+        return;
+    }
+    let per_group = total / groups;
+    if per_group > MAX_GROUP_SIZE {
+        return;
+    }
+    let palette = theme::palette();
+    let slot = area.w / groups as f32;
+    let radius = (slot / 2.0 - 6.0).min(area.h / 2.0);
+    // Items sit in a small grid inside each circle.
+    let columns = (per_group as f32).sqrt().ceil().max(1.0) as i32;
+    let rows = (per_group + columns - 1) / columns;
+    let cell = radius * 1.3 / columns.max(rows) as f32;
+    for g in 0..groups {
+        let cx = area.x + slot * (g as f32 + 0.5);
+        let cy = area.y + area.h / 2.0;
+        draw_circle_lines(cx, cy, radius, 2.0, palette.text);
+        let left = cx - cell * columns as f32 / 2.0;
+        let top = cy - cell * rows as f32 / 2.0;
+        for i in 0..per_group {
+            let x = left + ((i % columns) as f32 + 0.5) * cell;
+            let y = top + ((i / columns) as f32 + 0.5) * cell;
+            draw_circle(x, y, cell * 0.35, palette.good);
+        }
+    }
+}
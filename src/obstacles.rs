@@ -0,0 +1,379 @@
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+
+use ::rand as ext_rand;
+use ext_rand::Rng;
+
+use crate::input::Seat;
+use crate::theme;
+use crate::{new_player, overlaps, MultipleChoice, Player, ALIEN_WALL, GROUND_Y, SCREEN_WIDTH};
+
+// One more hazard for every `LEVEL_STEP` of score or mission difficulty.
+const LEVEL_STEP: i32 = 500;
+const MAX_HAZARDS: i32 = 3;
+const MAX_PLATFORMS: usize = 2;
+// Layouts tried before giving up on hazards for this question.
+const ATTEMPTS: usize = 20;
+
+// Hazards stay between the shuttles and the ground, and away from the
+// launch spots, so the astronauts always have room to take off.
+const BAND_TOP: f32 = 300.0;
+const BAND_BOTTOM: f32 = 520.0;
+const LAUNCH_CLEARANCE: f32 = 200.0;
+
+// Hitbox of a shuttle, matching the collision check in `main`.
+const SHUTTLE_WIDTH: f32 = 100.0;
+const SHUTTLE_HEIGHT: f32 = 80.0;
+
+const ASTEROID_BOB: f32 = 10.0; // Pixels up and down.
+const LASER_WIDTH: f32 = 150.0;
+const LASER_PERIOD: f32 = 3.0; // Seconds for one on-off cycle.
+const LASER_ON: f32 = 0.6; // Fraction of the cycle the beam is on.
+const PLATFORM_WIDTH: f32 = 120.0;
+const PLATFORM_HEIGHT: f32 = 14.0;
+
+// Cell size of the grid the reachability check flies the astronaut over.
+const GRID: f32 = 20.0;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Asteroid { radius: f32 },
+    LaserGate,
+    Platform, // Can be stood on from above and flown through from below.
+}
+
+struct Obstacle {
+    kind: Kind,
+    x: f32, // Center for asteroids, top-left otherwise.
+    y: f32,
+    phase: f32, // Seconds, so obstacles don't all move in step.
+}
+
+impl Obstacle {
+    // Everywhere the obstacle can hurt over its whole cycle.
+    fn danger_zone(&self) -> Option<Rect> {
+        match self.kind {
+            Kind::Asteroid { radius } => Some(Rect::new(
+                self.x - radius,
+                self.y - radius - ASTEROID_BOB,
+                radius * 2.0,
+                (radius + ASTEROID_BOB) * 2.0,
+            )),
+            Kind::LaserGate => Some(Rect::new(self.x, self.y, LASER_WIDTH, 10.0)),
+            Kind::Platform => None,
+        }
+    }
+
+    fn asteroid_y(&self, time: f32) -> f32 {
+        self.y + ((time + self.phase) * 2.0).sin() * ASTEROID_BOB
+    }
+
+    fn laser_on(&self, time: f32) -> bool {
+        (time + self.phase).rem_euclid(LASER_PERIOD) < LASER_PERIOD * LASER_ON
+    }
+}
+
+/// The asteroids, laser gates and platforms between the astronaut and the
+/// shuttles. A new layout is generated for every question.
+pub struct Obstacles {
+    items: Vec<Obstacle>,
+    time: f32,
+}
+
+impl Obstacles {
+    pub fn none() -> Obstacles {
+        Obstacles {
+            items: Vec::new(),
+            time: 0.0,
+        }
+    }
+
+    /// A random layout for `level` (a score or mission difficulty) in
+    /// which every shuttle in `choices` can still be reached. If no such
+    /// layout turns up, the question gets platforms only.
+    pub fn generate(choices: &[MultipleChoice], level: i32) -> Obstacles {
+        let mut rng = ext_rand::rng();
+        let hazards = (level / LEVEL_STEP).clamp(0, MAX_HAZARDS);
+        let platforms = rng.random_range(1..=MAX_PLATFORMS);
+        let mut layout = Obstacles::none();
+        for _ in 0..platforms {
+            layout.items.push(Obstacle {
+                kind: Kind::Platform,
+                x: rng.random_range(ALIEN_WALL + LAUNCH_CLEARANCE..SCREEN_WIDTH - PLATFORM_WIDTH),
+                y: rng.random_range(BAND_TOP + 80.0..BAND_BOTTOM),
+                phase: 0.0,
+            });
+        }
+        for _ in 0..ATTEMPTS {
+            let mut attempt = Vec::new();
+            for _ in 0..hazards {
+                let phase = rng.random_range(0.0..LASER_PERIOD);
+                let x = rng.random_range(ALIEN_WALL + LAUNCH_CLEARANCE..SCREEN_WIDTH - LASER_WIDTH);
+                let y = rng.random_range(BAND_TOP..BAND_BOTTOM);
+                attempt.push(if rng.random_bool(0.5) {
+                    let radius = rng.random_range(18.0..34.0);
+                    Obstacle {
+                        kind: Kind::Asteroid { radius },
+                        x: x + radius,
+                        y,
+                        phase,
+                    }
+                } else {
+                    Obstacle {
+                        kind: Kind::LaserGate,
+                        x,
+                        y,
+                        phase,
+                    }
+                });
+            }
+            if all_reachable(&attempt, choices) {
+                layout.items.extend(attempt);
+                break;
+            }
+        }
+        layout
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.time += dt;
+    }
+
+    /// Whether the astronaut is touching an asteroid or a live laser.
+    pub fn hits(&self, player: &Player) -> bool {
+        self.items.iter().any(|item| match item.kind {
+            Kind::Asteroid { radius } => {
+                let center = vec2(item.x, item.asteroid_y(self.time));
+                let closest = vec2(
+                    center.x.clamp(player.x, player.x + player.width),
+                    center.y.clamp(player.y, player.y + player.height),
+                );
+                closest.distance(center) < radius
+            }
+            Kind::LaserGate => {
+                item.laser_on(self.time)
+                    && overlaps(
+                        player.x,
+                        player.y,
+                        player.width,
+                        player.height,
+                        item.x,
+                        item.y,
+                        LASER_WIDTH,
+                        10.0,
+                    )
+            }
+            Kind::Platform => false,
+        })
+    }
+
+    /// Stops a falling astronaut on top of a platform.
+    pub fn support(&self, player: &mut Player) {
+        if player.vy <= 0.0 {
+            return;
+        }
+        let feet = player.y + player.height;
+        for item in self.items.iter().filter(|item| item.kind == Kind::Platform) {
+            let across = player.x + player.width > item.x && player.x < item.x + PLATFORM_WIDTH;
+            // Only if the feet were above the platform last frame.
+            if across && feet >= item.y && feet - player.vy <= item.y {
+                player.y = item.y - player.height;
+                player.vy = 0.0;
+            }
+        }
+    }
+
+    pub fn draw(&self) {
+        let palette = theme::palette();
+        for item in &self.items {
+            match item.kind {
+                Kind::Asteroid { radius } => {
+                    let y = item.asteroid_y(self.time);
+                    let rotation = (self.time + item.phase) * 30.0;
+                    draw_poly(item.x, y, 7, radius, rotation, palette.muted);
+                    draw_poly_lines(item.x, y, 7, radius, rotation, 2.0, palette.text);
+                }
+                Kind::LaserGate => {
+                    draw_rectangle(item.x - 8.0, item.y - 7.0, 12.0, 24.0, palette.muted);
+                    draw_rectangle(
+                        item.x + LASER_WIDTH - 4.0,
+                        item.y - 7.0,
+                        12.0,
+                        24.0,
+                        palette.muted,
+                    );
+                    if item.laser_on(self.time) {
+                        let mut glow = palette.bad;
+                        glow.a = 0.35;
+                        draw_rectangle(item.x, item.y - 4.0, LASER_WIDTH, 18.0, glow);
+                        draw_rectangle(item.x, item.y + 2.0, LASER_WIDTH, 6.0, palette.bad);
+                    } else {
+                        // A faint dashed line shows where the beam will be.
+                        let mut dash = palette.bad;
+                        dash.a = 0.3;
+                        for i in (0..LASER_WIDTH as i32).step_by(20) {
+                            draw_rectangle(item.x + i as f32, item.y + 4.0, 10.0, 2.0, dash);
+                        }
+                    }
+                }
+                Kind::Platform => {
+                    draw_rectangle(
+                        item.x,
+                        item.y,
+                        PLATFORM_WIDTH,
+                        PLATFORM_HEIGHT,
+                        palette.ground,
+                    );
+                    draw_rectangle_lines(
+                        item.x,
+                        item.y,
+                        PLATFORM_WIDTH,
+                        PLATFORM_HEIGHT,
+                        2.0,
+                        palette.text,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Flies the astronaut's hitbox over a grid from the launch spot and checks
+/// every shuttle can be touched without entering a hazard. Lasers count as
+/// always on and asteroids as filling their whole bob.
+fn all_reachable(hazards: &[Obstacle], choices: &[MultipleChoice]) -> bool {
+    let start = new_player(Seat::Solo);
+    let (w, h) = (start.width, start.height);
+    let columns = ((SCREEN_WIDTH - w - ALIEN_WALL) / GRID) as usize + 1;
+    let rows = (GROUND_Y / GRID) as usize + 1;
+    let position = |column: usize, row: usize| {
+        (
+            ALIEN_WALL + column as f32 * GRID,
+            (row as f32 * GRID).min(GROUND_Y),
+        )
+    };
+    let zones: Vec<Rect> = hazards.iter().filter_map(Obstacle::danger_zone).collect();
+    let free = |column: usize, row: usize| {
+        let (x, y) = position(column, row);
+        !zones
+            .iter()
+            .any(|zone| overlaps(x, y, w, h, zone.x, zone.y, zone.w, zone.h))
+    };
+
+    let mut seen = vec![false; columns * rows];
+    let mut queue = VecDeque::new();
+    let start_cell = (0, rows - 1);
+    if free(start_cell.0, start_cell.1) {
+        seen[start_cell.1 * columns + start_cell.0] = true;
+        queue.push_back(start_cell);
+    }
+    while let Some((column, row)) = queue.pop_front() {
+        let neighbors = [
+            (column.wrapping_sub(1), row),
+            (column + 1, row),
+            (column, row.wrapping_sub(1)),
+            (column, row + 1),
+        ];
+        for (c, r) in neighbors {
+            if c < columns && r < rows && !seen[r * columns + c] && free(c, r) {
+                seen[r * columns + c] = true;
+                queue.push_back((c, r));
+            }
+        }
+    }
+
+    choices.iter().all(|choice| {
+        (0..rows).any(|row| {
+            (0..columns).any(|column| {
+                let (x, y) = position(column, row);
+                seen[row * columns + column]
+                    && overlaps(
+                        x,
+                        y,
+                        w,
+                        h,
+                        choice.x,
+                        choice.y,
+                        SHUTTLE_WIDTH,
+                        SHUTTLE_HEIGHT,
+                    )
+            })
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Four shuttles where `question_for_fact` puts them.
+    fn shuttles() -> Vec<MultipleChoice> {
+        let slot_width = (SCREEN_WIDTH - 200.0) / 4.0;
+        (0..4)
+            .map(|i| MultipleChoice {
+                x: 100.0 + slot_width * (i as f32 + 0.5) - 40.0,
+                y: 200.0,
+                text: i.to_string(),
+                is_correct: i == 0,
+            })
+            .collect()
+    }
+
+    fn laser(x: f32, y: f32) -> Obstacle {
+        Obstacle {
+            kind: Kind::LaserGate,
+            x,
+            y,
+            phase: 0.0,
+        }
+    }
+
+    #[test]
+    fn open_sky_is_reachable() {
+        assert!(all_reachable(&[], &shuttles()));
+    }
+
+    #[test]
+    fn walled_off_shuttle_is_rejected() {
+        // A slab of lasers around the first shuttle, too tightly stacked
+        // for the astronaut to slip between.
+        let walls: Vec<Obstacle> = (0..7)
+            .flat_map(|row| {
+                let y = 120.0 + row as f32 * 40.0;
+                [laser(ALIEN_WALL, y), laser(ALIEN_WALL + LASER_WIDTH, y)]
+            })
+            .collect();
+        let choices = shuttles();
+        assert!(!all_reachable(&walls, &choices));
+        assert!(all_reachable(&walls, &choices[1..]));
+    }
+
+    #[test]
+    fn generated_layouts_are_reachable() {
+        let choices = shuttles();
+        for level in (0..=2000).step_by(LEVEL_STEP as usize) {
+            let expected = (level / LEVEL_STEP).min(MAX_HAZARDS) as usize;
+            let mut with_hazards = 0;
+            for _ in 0..100 {
+                let layout = Obstacles::generate(&choices, level);
+                assert!(all_reachable(&layout.items, &choices));
+                let hazards = layout
+                    .items
+                    .iter()
+                    .filter(|item| item.kind != Kind::Platform)
+                    .count();
+                // Either the full set for the level or the platforms-only
+                // fallback, never a partial one.
+                assert!(hazards == expected || hazards == 0, "level {}", level);
+                if hazards > 0 {
+                    with_hazards += 1;
+                }
+            }
+            // The fallback should be rare, or hard levels would have no
+            // hazards at all.
+            if expected > 0 {
+                assert!(with_hazards >= 80, "level {}: {}/100", level, with_hazards);
+            }
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use macroquad::prelude::*;
+
+use crate::input::{Action, Input};
+use crate::theme;
+use crate::SCREEN_WIDTH;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Key {
+    Char(char),
+    Delete,
+    Done,
+}
+
+// Key grid, navigated with the move actions.
+const ROWS: [&str; 4] = ["ABCDEFGHIJ", "KLMNOPQRST", "UVWXYZ0123", "456789"];
+const KEY_SIZE: f32 = 52.0;
+const KEY_GAP: f32 = 8.0;
+// Extra width of the Delete/Done keys that end the last row.
+const WIDE_KEY: f32 = KEY_SIZE * 2.0 + KEY_GAP;
+
+/// What pressing a key on the on-screen keyboard did.
+pub enum KeyboardEvent {
+    None,
+    Done,
+}
+
+/// A letter grid for typing a name with a gamepad, mouse or touchscreen.
+///
+/// The cursor stays hidden until a direction is pressed, so Enter on a
+/// physical keyboard still finishes the name instead of typing a letter.
+pub struct OnScreenKeyboard {
+    row: usize,
+    col: usize,
+    engaged: bool,
+    top: f32, // Screen y of the first row.
+}
+
+impl OnScreenKeyboard {
+    pub fn new(top: f32) -> OnScreenKeyboard {
+        OnScreenKeyboard {
+            row: 0,
+            col: 0,
+            engaged: false,
+            top,
+        }
+    }
+
+    /// Screen rectangle of every key, as (row, column, key, rect).
+    fn layout(&self) -> Vec<(usize, usize, Key, Rect)> {
+        let mut keys = Vec::new();
+        for row in 0..ROWS.len() {
+            let row_keys = Self::row_keys(row);
+            let row_width: f32 = row_keys
+                .iter()
+                .map(|&k| key_width(k) + KEY_GAP)
+                .sum::<f32>()
+                - KEY_GAP;
+            let mut x = (SCREEN_WIDTH - row_width) / 2.0;
+            let y = self.top + row as f32 * (KEY_SIZE + KEY_GAP);
+            for (col, &key) in row_keys.iter().enumerate() {
+                let w = key_width(key);
+                keys.push((row, col, key, Rect::new(x, y, w, KEY_SIZE)));
+                x += w + KEY_GAP;
+            }
+        }
+        keys
+    }
+
+    fn row_keys(row: usize) -> Vec<Key> {
+        let mut keys: Vec<Key> = ROWS[row].chars().map(Key::Char).collect();
+        if row == ROWS.len() - 1 {
+            keys.push(Key::Delete);
+            keys.push(Key::Done);
+        }
+        keys
+    }
+
+    /// Whether Confirm should press the highlighted key.
+    pub fn is_engaged(&self) -> bool {
+        self.engaged
+    }
+
+    /// Typing on a physical keyboard hides the cursor again.
+    pub fn disengage(&mut self) {
+        self.engaged = false;
+    }
+
+    /// Moves the cursor and presses keys. Edits `name` in place.
+    pub fn update(&mut self, input: &Input, name: &mut String) -> KeyboardEvent {
+        // Tapping a key presses it straight away.
+        let tapped = self
+            .layout()
+            .into_iter()
+            .find(|&(_, _, _, rect)| input.tapped(rect));
+        if let Some((row, col, key, _)) = tapped {
+            self.row = row;
+            self.col = col;
+            return press(key, name);
+        }
+
+        let directions = [
+            Action::MoveLeft,
+            Action::MoveRight,
+            Action::Up,
+            Action::Down,
+        ];
+        if directions.iter().any(|&a| input.is_pressed(a)) {
+            if !self.engaged {
+                // The first press only reveals the cursor.
+                self.engaged = true;
+                return KeyboardEvent::None;
+            }
+            if input.is_pressed(Action::MoveLeft) && self.col > 0 {
+                self.col -= 1;
+            }
+            if input.is_pressed(Action::MoveRight) && self.col + 1 < Self::row_keys(self.row).len()
+            {
+                self.col += 1;
+            }
+            if input.is_pressed(Action::Up) && self.row > 0 {
+                self.row -= 1;
+            }
+            if input.is_pressed(Action::Down) && self.row + 1 < ROWS.len() {
+                self.row += 1;
+            }
+            self.col = self.col.min(Self::row_keys(self.row).len() - 1);
+        }
+
+        if self.engaged && input.is_pressed(Action::Confirm) {
+            return press(Self::row_keys(self.row)[self.col], name);
+        }
+        if self.engaged && input.is_pressed(Action::Back) {
+            name.pop();
+        }
+        KeyboardEvent::None
+    }
+
+    /// Draws the key grid centred horizontally.
+    pub fn draw(&self) {
+        let palette = theme::palette();
+        for (row, col, key, rect) in self.layout() {
+            // The selected key is drawn inverted.
+            let selected = self.engaged && row == self.row && col == self.col;
+            let (fill, text) = if selected {
+                (palette.text, palette.background)
+            } else {
+                (palette.ground, palette.ground_text)
+            };
+            draw_rectangle(rect.x, rect.y, rect.w, rect.h, fill);
+            draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2.0, palette.muted);
+            let label = match key {
+                Key::Char(c) => c.to_string(),
+                Key::Delete => "DEL".to_string(),
+                Key::Done => "OK".to_string(),
+            };
+            let dims = theme::measure_text(&label, 30);
+            theme::draw_text(
+                &label,
+                rect.x + (rect.w - dims.width) / 2.0,
+                rect.y + (rect.h + dims.height) / 2.0,
+                30,
+                text,
+            );
+        }
+    }
+}
+
+fn press(key: Key, name: &mut String) -> KeyboardEvent {
+    match key {
+        Key::Char(c) => {
+            if name.len() < 15 {
+                name.push(c);
+            }
+        }
+        Key::Delete => {
+            name.pop();
+        }
+        Key::Done => return KeyboardEvent::Done,
+    }
+    KeyboardEvent::None
+}
+
+fn key_width(key: Key) -> f32 {
+    match key {
+        Key::Char(_) => KEY_SIZE,
+        Key::Delete | Key::Done => WIDE_KEY,
+    }
+}
@@ -0,0 +1,198 @@
+use macroquad::prelude::*;
+
+use ::rand as ext_rand;
+use ext_rand::Rng;
+
+use crate::theme;
+
+// Pool sizes. When a pool is full the oldest entry is reused.
+const MAX_PARTICLES: usize = 512;
+const MAX_TEXTS: usize = 16;
+
+// Jetpack exhaust, in particles per second.
+const EXHAUST_RATE: f32 = 60.0;
+const EXPLOSION_PARTICLES: usize = 40;
+const SPARKLE_PARTICLES: usize = 30;
+
+const TEXT_LIFE: f32 = 1.0; // Seconds a floating score stays up.
+const TEXT_RISE: f32 = 60.0; // Pixels per second.
+const SHAKE_TIME: f32 = 0.35;
+pub const LIFE_LOST_SHAKE: f32 = 12.0; // Pixels at the start of the shake.
+
+#[derive(Clone, Copy, Default)]
+struct Particle {
+    pos: Vec2,
+    vel: Vec2,
+    gravity: f32,
+    size: f32,
+    color: Color,
+    life: f32, // Seconds left; free when it runs out.
+    max_life: f32,
+}
+
+#[derive(Clone, Default)]
+struct FloatingText {
+    text: String,
+    pos: Vec2,
+    color: Color,
+    life: f32,
+}
+
+/// Exhaust, explosions, sparkles, floating score text and screen shake.
+/// Everything lives in fixed pools and moves by frame time.
+pub struct Particles {
+    particles: Vec<Particle>,
+    next_particle: usize,
+    texts: Vec<FloatingText>,
+    next_text: usize,
+    exhaust_due: f32, // Fraction of an exhaust particle carried to the next frame.
+    shake_time: f32,
+    shake_strength: f32,
+}
+
+impl Particles {
+    pub fn new() -> Particles {
+        Particles {
+            particles: vec![Particle::default(); MAX_PARTICLES],
+            next_particle: 0,
+            texts: vec![FloatingText::default(); MAX_TEXTS],
+            next_text: 0,
+            exhaust_due: 0.0,
+            shake_time: 0.0,
+            shake_strength: 0.0,
+        }
+    }
+
+    /// Removes everything, e.g. when a new run starts.
+    pub fn clear(&mut self) {
+        self.particles.iter_mut().for_each(|p| p.life = 0.0);
+        self.texts.iter_mut().for_each(|t| t.life = 0.0);
+        self.shake_time = 0.0;
+    }
+
+    fn spawn(&mut self, particle: Particle) {
+        self.particles[self.next_particle] = particle;
+        self.next_particle = (self.next_particle + 1) % MAX_PARTICLES;
+    }
+
+    /// Puffs from the jetpack at `pos`, called every frame it fires.
+    pub fn exhaust(&mut self, pos: Vec2, dt: f32) {
+        let mut rng = ext_rand::rng();
+        self.exhaust_due += EXHAUST_RATE * dt;
+        while self.exhaust_due >= 1.0 {
+            self.exhaust_due -= 1.0;
+            let life = rng.random_range(0.25..0.5);
+            self.spawn(Particle {
+                pos: pos + vec2(rng.random_range(-6.0..6.0), 0.0),
+                vel: vec2(
+                    rng.random_range(-30.0..30.0),
+                    rng.random_range(120.0..200.0),
+                ),
+                gravity: 0.0,
+                size: rng.random_range(3.0..6.0),
+                color: if rng.random_bool(0.5) { ORANGE } else { YELLOW },
+                life,
+                max_life: life,
+            });
+        }
+    }
+
+    /// A burst of debris from a wrong shuttle.
+    pub fn explosion(&mut self, pos: Vec2) {
+        let mut rng = ext_rand::rng();
+        let bad = theme::palette().bad;
+        for _ in 0..EXPLOSION_PARTICLES {
+            let angle = rng.random_range(0.0..std::f32::consts::TAU);
+            let speed = rng.random_range(80.0..320.0);
+            let life = rng.random_range(0.4..0.9);
+            self.spawn(Particle {
+                pos,
+                vel: Vec2::from_angle(angle) * speed,
+                gravity: 400.0,
+                size: rng.random_range(3.0..8.0),
+                color: if rng.random_bool(0.5) { bad } else { ORANGE },
+                life,
+                max_life: life,
+            });
+        }
+    }
+
+    /// Slow twinkles drifting up from the correct shuttle.
+    pub fn sparkles(&mut self, pos: Vec2) {
+        let mut rng = ext_rand::rng();
+        let good = theme::palette().good;
+        for _ in 0..SPARKLE_PARTICLES {
+            let life = rng.random_range(0.5..1.0);
+            self.spawn(Particle {
+                pos: pos + vec2(rng.random_range(-60.0..60.0), rng.random_range(-30.0..30.0)),
+                vel: vec2(
+                    rng.random_range(-40.0..40.0),
+                    rng.random_range(-120.0..-30.0),
+                ),
+                gravity: 0.0,
+                size: rng.random_range(2.0..5.0),
+                color: if rng.random_bool(0.5) { good } else { GOLD },
+                life,
+                max_life: life,
+            });
+        }
+    }
+
+    /// Text that rises from `pos` and fades, e.g. "+100".
+    pub fn float_text(&mut self, text: &str, pos: Vec2, color: Color) {
+        self.texts[self.next_text] = FloatingText {
+            text: text.to_string(),
+            pos,
+            color,
+            life: TEXT_LIFE,
+        };
+        self.next_text = (self.next_text + 1) % MAX_TEXTS;
+    }
+
+    /// Shakes the screen, starting `strength` pixels out and settling down.
+    pub fn shake(&mut self, strength: f32) {
+        self.shake_time = SHAKE_TIME;
+        self.shake_strength = strength;
+    }
+
+    /// How far to move the view this frame.
+    pub fn shake_offset(&self) -> Vec2 {
+        if self.shake_time <= 0.0 {
+            return Vec2::ZERO;
+        }
+        let mut rng = ext_rand::rng();
+        let strength = self.shake_strength * self.shake_time / SHAKE_TIME;
+        vec2(
+            rng.random_range(-1.0..=1.0) * strength,
+            rng.random_range(-1.0..=1.0) * strength,
+        )
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for p in self.particles.iter_mut().filter(|p| p.life > 0.0) {
+            p.life -= dt;
+            p.vel.y += p.gravity * dt;
+            p.pos += p.vel * dt;
+        }
+        for t in self.texts.iter_mut().filter(|t| t.life > 0.0) {
+            t.life -= dt;
+            t.pos.y -= TEXT_RISE * dt;
+        }
+        self.shake_time = (self.shake_time - dt).max(0.0);
+    }
+
+    pub fn draw(&self) {
+        for p in self.particles.iter().filter(|p| p.life > 0.0) {
+            let k = p.life / p.max_life;
+            let color = Color::new(p.color.r, p.color.g, p.color.b, p.color.a * k);
+            draw_circle(p.pos.x, p.pos.y, p.size * (0.5 + k * 0.5), color);
+        }
+        for t in self.texts.iter().filter(|t| t.life > 0.0) {
+            let k = t.life / TEXT_LIFE;
+            let color = Color::new(t.color.r, t.color.g, t.color.b, k);
+            let size = theme::scaled(36);
+            let width = theme::measure_text(&t.text, size).width;
+            theme::draw_text(&t.text, t.pos.x - width / 2.0, t.pos.y, size, color);
+        }
+    }
+}
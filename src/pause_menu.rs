@@ -0,0 +1,144 @@
+use macroquad::prelude::*;
+
+use crate::input::{Action, Input};
+use crate::theme;
+use crate::{draw_centered_text, GameState, SCREEN_HEIGHT, SCREEN_WIDTH};
+
+/// What the pause menu wants the caller to do next.
+#[derive(Clone, Copy)]
+pub enum PauseEvent {
+    None,
+    Resume,
+    Restart,
+    Settings,
+    Quit,
+}
+
+const ITEMS: [(&str, PauseEvent); 4] = [
+    ("Resume", PauseEvent::Resume),
+    ("Restart", PauseEvent::Restart),
+    ("Settings", PauseEvent::Settings),
+    ("Quit to menu", PauseEvent::Quit),
+];
+
+// Seconds of "3, 2, 1" before play continues after a pause.
+pub const RESUME_COUNTDOWN: f32 = 3.0;
+
+fn item_rect(i: usize) -> Rect {
+    let y = SCREEN_HEIGHT / 2.0 - 60.0 + i as f32 * 55.0;
+    Rect::new(SCREEN_WIDTH / 2.0 - 150.0, y - 35.0, 300.0, 48.0)
+}
+
+/// The on-screen pause button shown during play, below the score.
+pub fn button_rect() -> Rect {
+    Rect::new(SCREEN_WIDTH - 70.0, 65.0, 50.0, 50.0)
+}
+
+pub fn draw_button() {
+    let palette = theme::palette();
+    let rect = button_rect();
+    let mut fill = palette.background;
+    fill.a = 0.8;
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, fill);
+    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2.0, palette.text);
+    // Two bars, the usual pause symbol.
+    draw_rectangle(rect.x + 14.0, rect.y + 12.0, 8.0, 26.0, palette.text);
+    draw_rectangle(rect.x + 28.0, rect.y + 12.0, 8.0, 26.0, palette.text);
+}
+
+/// The overlay opened with the Pause action during a run.
+pub struct PauseMenu {
+    selected: usize,
+    // Time left on the post-answer freeze the run was paused in, if any.
+    freeze: Option<f32>,
+}
+
+impl PauseMenu {
+    pub fn new() -> PauseMenu {
+        PauseMenu {
+            selected: 0,
+            freeze: None,
+        }
+    }
+
+    /// Opens the menu, remembering whether the run was in a post-answer freeze.
+    pub fn open(&mut self, freeze: Option<f32>) {
+        self.selected = 0;
+        self.freeze = freeze;
+    }
+
+    /// The state to go back to once the resume countdown is over.
+    pub fn resumed_state(&self) -> GameState {
+        match self.freeze {
+            Some(time_left) => GameState::Freeze(time_left),
+            None => GameState::Playing,
+        }
+    }
+
+    pub fn update(&mut self, input: &Input) -> PauseEvent {
+        if input.is_pressed(Action::Pause) || input.tapped(button_rect()) {
+            return PauseEvent::Resume;
+        }
+        if input.is_pressed(Action::Up) && self.selected > 0 {
+            self.selected -= 1;
+        } else if input.is_pressed(Action::Down) && self.selected + 1 < ITEMS.len() {
+            self.selected += 1;
+        }
+        if let Some(i) = (0..ITEMS.len()).find(|&i| input.tapped(item_rect(i))) {
+            return ITEMS[i].1;
+        }
+        if input.is_pressed(Action::Confirm) {
+            return ITEMS[self.selected].1;
+        }
+        PauseEvent::None
+    }
+
+    /// Dims the frozen scene and lists the menu entries on top of it.
+    pub fn draw(&self) {
+        draw_rectangle(
+            0.0,
+            0.0,
+            SCREEN_WIDTH,
+            SCREEN_HEIGHT,
+            Color::new(0.0, 0.0, 0.0, 0.5),
+        );
+        // A plate in the theme's colors keeps the menu readable over any scene.
+        let palette = theme::palette();
+        let first = item_rect(0);
+        let last = item_rect(ITEMS.len() - 1);
+        let plate = Rect::new(
+            first.x - 30.0,
+            SCREEN_HEIGHT / 2.0 - 190.0,
+            first.w + 60.0,
+            last.bottom() + 20.0 - (SCREEN_HEIGHT / 2.0 - 190.0),
+        );
+        draw_rectangle(plate.x, plate.y, plate.w, plate.h, palette.background);
+        draw_rectangle_lines(plate.x, plate.y, plate.w, plate.h, 3.0, palette.muted);
+        draw_centered_text("Paused", SCREEN_HEIGHT / 2.0 - 130.0, 60, palette.text);
+        for (i, (label, _)) in ITEMS.iter().enumerate() {
+            let rect = item_rect(i);
+            if i == self.selected {
+                draw_rectangle(rect.x, rect.y, rect.w, rect.h, palette.text);
+            }
+            let color = if i == self.selected {
+                palette.background
+            } else {
+                palette.text
+            };
+            draw_centered_text(label, rect.y + 35.0, 36, color);
+        }
+    }
+}
+
+/// Draws the big "3, 2, 1" over the scene while resuming.
+pub fn draw_countdown(time_left: f32) {
+    draw_rectangle(
+        0.0,
+        0.0,
+        SCREEN_WIDTH,
+        SCREEN_HEIGHT,
+        Color::new(0.0, 0.0, 0.0, 0.3),
+    );
+    let number = time_left.ceil().max(1.0).to_string();
+    draw_centered_text(&number, SCREEN_HEIGHT / 2.0, 120, theme::palette().text);
+}
@@ -0,0 +1,96 @@
+/// How the astronaut moves: gravity, jetpack strength and steering.
+pub struct Physics {
+    /// Downward pull, in pixels per frame per frame.
+    pub gravity: f32,
+    /// Upward push of the jetpack, in pixels per frame per frame.
+    pub boost: f32,
+    /// Top walking and flying speed, in pixels per frame.
+    pub move_speed: f32,
+    /// How much of the gap to the wanted speed is closed each frame. At 1.0
+    /// the astronaut starts and stops at once; lower values drift.
+    pub steering: f32,
+    /// Whether Down fires the jetpack downwards, for when gravity is too
+    /// weak to get back down.
+    pub thrust_down: bool,
+}
+
+/// Ready-made physics, tied to the environments or picked on the settings screen.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Profile {
+    Earth,
+    Moon, // Low gravity and a gentle jetpack: long, floaty jumps.
+    Mars,
+    Jupiter, // Heavy gravity and a strong jetpack.
+    ZeroG,   // No gravity; the astronaut keeps drifting until steered.
+}
+
+impl Profile {
+    pub const ALL: [Profile; 5] = [
+        Profile::Earth,
+        Profile::Moon,
+        Profile::Mars,
+        Profile::Jupiter,
+        Profile::ZeroG,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Profile::Earth => "Earth",
+            Profile::Moon => "Moon",
+            Profile::Mars => "Mars",
+            Profile::Jupiter => "Jupiter",
+            Profile::ZeroG => "Zero-g",
+        }
+    }
+
+    /// Name used in the settings file.
+    pub fn id(self) -> &'static str {
+        match self {
+            Profile::Earth => "earth",
+            Profile::Moon => "moon",
+            Profile::Mars => "mars",
+            Profile::Jupiter => "jupiter",
+            Profile::ZeroG => "zero_g",
+        }
+    }
+
+    pub fn physics(self) -> Physics {
+        match self {
+            Profile::Earth => Physics {
+                gravity: 0.2,
+                boost: 0.3,
+                move_speed: 3.0,
+                steering: 1.0,
+                thrust_down: false,
+            },
+            Profile::Moon => Physics {
+                gravity: 0.08,
+                boost: 0.15,
+                move_speed: 2.5,
+                steering: 0.5,
+                thrust_down: false,
+            },
+            Profile::Mars => Physics {
+                gravity: 0.12,
+                boost: 0.22,
+                move_speed: 3.0,
+                steering: 0.8,
+                thrust_down: false,
+            },
+            Profile::Jupiter => Physics {
+                gravity: 0.45,
+                boost: 0.6,
+                move_speed: 3.5,
+                steering: 1.0,
+                thrust_down: false,
+            },
+            Profile::ZeroG => Physics {
+                gravity: 0.0,
+                boost: 0.1,
+                move_speed: 3.5,
+                steering: 0.04,
+                thrust_down: true,
+            },
+        }
+    }
+}
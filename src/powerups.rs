@@ -0,0 +1,229 @@
+use macroquad::prelude::*;
+
+use ::rand as ext_rand;
+use ext_rand::prelude::*;
+
+use crate::theme;
+use crate::{overlaps, MultipleChoice, Player, ALIEN_WALL, GROUND_Y, SCREEN_WIDTH};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PowerUpKind {
+    Shield,     // Absorbs the next wrong shuttle hit.
+    SlowTime,   // Halves the alien's speed.
+    FiftyFifty, // Removes two wrong answers from each question.
+    ExtraLife,  // One more life, up to the starting amount.
+}
+
+impl PowerUpKind {
+    const ALL: [PowerUpKind; 4] = [
+        PowerUpKind::Shield,
+        PowerUpKind::SlowTime,
+        PowerUpKind::FiftyFifty,
+        PowerUpKind::ExtraLife,
+    ];
+
+    /// How long the power-up (or, for instant ones, its HUD indicator) lasts.
+    fn duration(self) -> f32 {
+        match self {
+            PowerUpKind::Shield => 15.0,
+            PowerUpKind::SlowTime => 8.0,
+            PowerUpKind::FiftyFifty => 10.0,
+            PowerUpKind::ExtraLife => 2.0,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            PowerUpKind::Shield => "S",
+            PowerUpKind::SlowTime => "T",
+            PowerUpKind::FiftyFifty => "50",
+            PowerUpKind::ExtraLife => "+1",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            PowerUpKind::Shield => SKYBLUE,
+            PowerUpKind::SlowTime => PURPLE,
+            PowerUpKind::FiftyFifty => ORANGE,
+            PowerUpKind::ExtraLife => PINK,
+        }
+    }
+}
+
+// Correct answers in a row needed to spawn a power-up.
+const STREAK_FOR_POWERUP: u32 = 3;
+// Seconds an uncollected power-up floats before disappearing.
+const PICKUP_LIFETIME: f32 = 8.0;
+const PICKUP_RADIUS: f32 = 18.0;
+// Fraction of normal speed the alien moves at during slow-time.
+const SLOW_TIME_FACTOR: f32 = 0.5;
+
+/// A power-up floating on the field, waiting to be collected.
+struct Pickup {
+    kind: PowerUpKind,
+    x: f32,
+    y: f32,
+    age: f32,
+}
+
+struct ActivePowerUp {
+    kind: PowerUpKind,
+    time_left: f32,
+}
+
+/// Streak tracking, the power-up waiting on the field, and active effects.
+pub struct PowerUps {
+    streak: u32,
+    pickup: Option<Pickup>,
+    active: Vec<ActivePowerUp>,
+}
+
+impl PowerUps {
+    pub fn new() -> PowerUps {
+        PowerUps {
+            streak: 0,
+            pickup: None,
+            active: Vec::new(),
+        }
+    }
+
+    /// Counts a correct answer and spawns a power-up after every streak.
+    pub fn record_correct(&mut self) {
+        self.streak += 1;
+        if self.streak.is_multiple_of(STREAK_FOR_POWERUP) && self.pickup.is_none() {
+            let mut rng = ext_rand::rng();
+            self.pickup = Some(Pickup {
+                kind: *PowerUpKind::ALL.choose(&mut rng).unwrap(),
+                x: rng.random_range(ALIEN_WALL + 100.0..SCREEN_WIDTH - 100.0),
+                y: rng.random_range(320.0..GROUND_Y - 20.0),
+                age: 0.0,
+            });
+        }
+    }
+
+    /// A wrong answer or a landed alien breaks the streak.
+    pub fn record_mistake(&mut self) {
+        self.streak = 0;
+    }
+
+    /// Ticks the timers and returns the kind of power-up the player just
+    /// collected, if any. The caller applies instant effects.
+    pub fn update(&mut self, dt: f32, player: &Player) -> Option<PowerUpKind> {
+        for power_up in &mut self.active {
+            power_up.time_left -= dt;
+        }
+        self.active.retain(|p| p.time_left > 0.0);
+
+        let pickup = self.pickup.as_mut()?;
+        pickup.age += dt;
+        if pickup.age > PICKUP_LIFETIME {
+            self.pickup = None;
+            return None;
+        }
+        let touched = overlaps(
+            player.x,
+            player.y,
+            player.width,
+            player.height,
+            pickup.x - PICKUP_RADIUS,
+            pickup.y - PICKUP_RADIUS,
+            PICKUP_RADIUS * 2.0,
+            PICKUP_RADIUS * 2.0,
+        );
+        if !touched {
+            return None;
+        }
+        let kind = pickup.kind;
+        self.pickup = None;
+        // Collecting a power-up that is already running restarts its timer.
+        self.active.retain(|p| p.kind != kind);
+        self.active.push(ActivePowerUp {
+            kind,
+            time_left: kind.duration(),
+        });
+        Some(kind)
+    }
+
+    pub fn is_active(&self, kind: PowerUpKind) -> bool {
+        self.active.iter().any(|p| p.kind == kind)
+    }
+
+    /// Uses up the shield, if there is one. Returns true when a hit was absorbed.
+    pub fn absorb_hit(&mut self) -> bool {
+        let had_shield = self.is_active(PowerUpKind::Shield);
+        self.active.retain(|p| p.kind != PowerUpKind::Shield);
+        had_shield
+    }
+
+    /// Multiplier for the alien's speed while slow-time runs.
+    pub fn alien_speed_factor(&self) -> f32 {
+        if self.is_active(PowerUpKind::SlowTime) {
+            SLOW_TIME_FACTOR
+        } else {
+            1.0
+        }
+    }
+
+    /// Draws the floating pickup, bobbing and blinking before it vanishes.
+    pub fn draw_pickup(&self) {
+        let Some(pickup) = &self.pickup else {
+            return;
+        };
+        let blinking = PICKUP_LIFETIME - pickup.age < 2.0 && (pickup.age * 8.0) as i32 % 2 == 0;
+        if blinking {
+            return;
+        }
+        let y = pickup.y + (pickup.age * 3.0).sin() * 6.0;
+        // Drawn bigger with bigger text, so the letter stays inside.
+        let radius = PICKUP_RADIUS * theme::text_scale();
+        draw_circle(pickup.x, y, radius, pickup.kind.color());
+        draw_circle_lines(pickup.x, y, radius, 2.0, WHITE);
+        draw_label(pickup.kind.label(), pickup.x, y);
+    }
+
+    /// Draws an icon and a draining timer bar for each active power-up,
+    /// right-aligned in the ground strip at `y`.
+    pub fn draw_hud(&self, y: f32) {
+        let scale = theme::text_scale();
+        let mut x = SCREEN_WIDTH - 40.0 * scale;
+        for power_up in &self.active {
+            let kind = power_up.kind;
+            draw_circle(x, y, 16.0 * scale, kind.color());
+            draw_label(kind.label(), x, y);
+            let fraction = power_up.time_left / kind.duration();
+            let (bar_x, bar_y, bar_width) = (x - 20.0 * scale, y + 22.0 * scale, 40.0 * scale);
+            draw_rectangle(bar_x, bar_y, bar_width, 6.0, DARKGRAY);
+            draw_rectangle(bar_x, bar_y, bar_width * fraction, 6.0, kind.color());
+            x -= 55.0 * scale;
+        }
+    }
+}
+
+fn draw_label(text: &str, x: f32, y: f32) {
+    let size = theme::scaled(24);
+    let dims = theme::measure_text(text, size);
+    theme::draw_text(
+        text,
+        x - dims.width / 2.0,
+        y + dims.height / 2.0,
+        size,
+        BLACK,
+    );
+}
+
+/// Takes two wrong shuttles away, leaving the correct one and one distractor.
+pub fn remove_two_wrong(choices: &mut Vec<MultipleChoice>) {
+    let mut wrong: Vec<usize> = (0..choices.len())
+        .filter(|&i| !choices[i].is_correct)
+        .collect();
+    if wrong.len() < 3 {
+        // Already reduced.
+        return;
+    }
+    wrong.shuffle(&mut ext_rand::rng());
+    let mut remove = [wrong[0], wrong[1]];
+    remove.sort_unstable();
+    choices.remove(remove[1]);
+    choices.remove(remove[0]);
+}
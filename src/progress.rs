@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+use crate::storage;
+
+const PROGRESS_FILE: &str = "progress.txt";
+
+/// Best star rating per mission, remembered for every player name.
+///
+/// Stored one player per line as `stars<TAB>name`, where `stars` is a
+/// comma-separated list indexed by mission, e.g. `3,2,0\tAda`.
+pub struct Progress {
+    players: HashMap<String, Vec<u8>>,
+}
+
+impl Progress {
+    /// Loads saved progress, starting fresh if there is none yet.
+    pub fn load() -> Progress {
+        let mut players = HashMap::new();
+        if let Some(contents) = storage::read_data_file(PROGRESS_FILE) {
+            for line in contents.lines() {
+                let Some((stars, name)) = line.split_once('\t') else {
+                    continue;
+                };
+                let stars = stars
+                    .split(',')
+                    .map(|s| s.trim().parse::<u8>().unwrap_or(0).min(3))
+                    .collect();
+                players.insert(name.to_string(), stars);
+            }
+        }
+        Progress { players }
+    }
+
+    fn save(&self) {
+        let mut contents = String::new();
+        for (name, stars) in &self.players {
+            let stars: Vec<String> = stars.iter().map(|s| s.to_string()).collect();
+            contents.push_str(&format!("{}\t{}\n", stars.join(","), name));
+        }
+        storage::write_data_file(PROGRESS_FILE, &contents);
+    }
+
+    /// Best stars `player` has earned on a mission (0 if never passed).
+    pub fn stars(&self, player: &str, mission: usize) -> u8 {
+        self.players
+            .get(player)
+            .and_then(|stars| stars.get(mission))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// A mission is open once the one before it has been passed.
+    pub fn is_unlocked(&self, player: &str, mission: usize) -> bool {
+        mission == 0 || self.stars(player, mission - 1) > 0
+    }
+
+    /// Records a mission result, keeping the best rating, and saves to disk.
+    pub fn record(&mut self, player: &str, mission: usize, stars: u8) {
+        let entry = self.players.entry(player.to_string()).or_default();
+        if entry.len() <= mission {
+            entry.resize(mission + 1, 0);
+        }
+        if stars > entry[mission] {
+            entry[mission] = stars;
+            self.save();
+        }
+    }
+}
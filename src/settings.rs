@@ -0,0 +1,217 @@
+use crate::physics::Profile;
+use crate::storage;
+use crate::theme::Theme;
+use crate::touch::TouchScheme;
+use crate::{Operation, INITIAL_LIVES};
+
+const SETTINGS_FILE: &str = "settings.cfg";
+
+// Window sizes offered on the settings screen.
+pub const WINDOW_SIZES: [(i32, i32); 4] = [(800, 600), (1024, 768), (1280, 960), (1600, 1200)];
+pub const MAX_LIVES: i32 = 10;
+pub const TEXT_SCALES: [f32; 4] = [1.0, 1.25, 1.5, 2.0];
+
+/// How the right answer is shown after a wrong shuttle or a landed alien.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AnswerReveal {
+    Off,
+    Brief,          // For `REVEAL_TIME` seconds, or until confirmed.
+    UntilConfirmed, // Until the player presses Confirm or taps.
+}
+
+impl AnswerReveal {
+    pub const ALL: [AnswerReveal; 3] = [
+        AnswerReveal::Off,
+        AnswerReveal::Brief,
+        AnswerReveal::UntilConfirmed,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AnswerReveal::Off => "Off",
+            AnswerReveal::Brief => "Briefly",
+            AnswerReveal::UntilConfirmed => "Until confirmed",
+        }
+    }
+
+    /// Name used in the settings file.
+    pub fn id(self) -> &'static str {
+        match self {
+            AnswerReveal::Off => "off",
+            AnswerReveal::Brief => "brief",
+            AnswerReveal::UntilConfirmed => "until_confirmed",
+        }
+    }
+}
+
+/// Player preferences, saved to `settings.cfg` in the config folder as
+/// `key = value` lines. Unknown or broken lines keep their defaults.
+#[derive(Clone, Debug)]
+pub struct Settings {
+    pub window_width: i32,
+    pub window_height: i32,
+    pub fullscreen: bool,
+    pub starting_lives: i32,
+    pub default_operation: Operation,
+    pub text_scale: f32,
+    pub theme: Theme,
+    pub dyslexic_font: bool,
+    pub answer_reveal: AnswerReveal,
+    pub manipulatives: bool, // Draw counters, dot arrays and groups for the operands.
+    pub physics: Option<Profile>, // None uses the environment's physics.
+    pub fuel: bool,          // The jetpack runs on fuel that refills on the ground.
+    pub music_volume: f32,   // 0.0 to 1.0
+    pub sfx_volume: f32,     // 0.0 to 1.0
+    pub touch_scheme: TouchScheme,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            window_width: 1024,
+            window_height: 768,
+            fullscreen: false,
+            starting_lives: INITIAL_LIVES,
+            default_operation: Operation::Addition,
+            text_scale: 1.0,
+            theme: Theme::Classic,
+            dyslexic_font: false,
+            answer_reveal: AnswerReveal::Brief,
+            manipulatives: false,
+            physics: None,
+            fuel: false,
+            music_volume: 0.7,
+            sfx_volume: 1.0,
+            touch_scheme: TouchScheme::TapToFly,
+        }
+    }
+}
+
+impl Settings {
+    pub fn load() -> Settings {
+        let mut settings = Settings::default();
+        let Some(contents) = storage::read_config_file(SETTINGS_FILE) else {
+            return settings;
+        };
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            if !settings.apply(key.trim(), value.trim()) {
+                eprintln!("Ignoring setting in {}: {}", SETTINGS_FILE, line);
+            }
+        }
+        settings
+    }
+
+    // Sets one value from the file, returning false if it can't be used.
+    fn apply(&mut self, key: &str, value: &str) -> bool {
+        match key {
+            "window_width" => parse_into(value, &mut self.window_width, 320..=7680),
+            "window_height" => parse_into(value, &mut self.window_height, 240..=4320),
+            "fullscreen" => value.parse().map(|v| self.fullscreen = v).is_ok(),
+            "starting_lives" => parse_into(value, &mut self.starting_lives, 1..=MAX_LIVES),
+            "default_operation" => match Operation::CYCLE.iter().find(|op| op.name() == value) {
+                Some(&op) => {
+                    self.default_operation = op;
+                    true
+                }
+                None => false,
+            },
+            "text_scale" => parse_into(value, &mut self.text_scale, 0.5..=3.0),
+            "theme" => match Theme::ALL.iter().find(|theme| theme.id() == value) {
+                Some(&theme) => {
+                    self.theme = theme;
+                    true
+                }
+                None => false,
+            },
+            "dyslexic_font" => value.parse().map(|v| self.dyslexic_font = v).is_ok(),
+            "answer_reveal" => match AnswerReveal::ALL.iter().find(|reveal| reveal.id() == value) {
+                Some(&reveal) => {
+                    self.answer_reveal = reveal;
+                    true
+                }
+                None => false,
+            },
+            "manipulatives" => value.parse().map(|v| self.manipulatives = v).is_ok(),
+            "physics" if value == "auto" => {
+                self.physics = None;
+                true
+            }
+            "physics" => match Profile::ALL.iter().find(|profile| profile.id() == value) {
+                Some(&profile) => {
+                    self.physics = Some(profile);
+                    true
+                }
+                None => false,
+            },
+            "fuel" => value.parse().map(|v| self.fuel = v).is_ok(),
+            "music_volume" => parse_into(value, &mut self.music_volume, 0.0..=1.0),
+            "sfx_volume" => parse_into(value, &mut self.sfx_volume, 0.0..=1.0),
+            "touch_scheme" => match TouchScheme::ALL.iter().find(|scheme| scheme.id() == value) {
+                Some(&scheme) => {
+                    self.touch_scheme = scheme;
+                    true
+                }
+                None => false,
+            },
+            _ => false,
+        }
+    }
+
+    pub fn save(&self) {
+        let contents = format!(
+            "# Mathnauts settings\n\
+             window_width = {}\n\
+             window_height = {}\n\
+             fullscreen = {}\n\
+             starting_lives = {}\n\
+             default_operation = {}\n\
+             text_scale = {}\n\
+             theme = {}\n\
+             dyslexic_font = {}\n\
+             answer_reveal = {}\n\
+             manipulatives = {}\n\
+             physics = {}\n\
+             fuel = {}\n\
+             music_volume = {}\n\
+             sfx_volume = {}\n\
+             touch_scheme = {}\n",
+            self.window_width,
+            self.window_height,
+            self.fullscreen,
+            self.starting_lives,
+            self.default_operation.name(),
+            self.text_scale,
+            self.theme.id(),
+            self.dyslexic_font,
+            self.answer_reveal.id(),
+            self.manipulatives,
+            self.physics.map_or("auto", Profile::id),
+            self.fuel,
+            self.music_volume,
+            self.sfx_volume,
+            self.touch_scheme.id(),
+        );
+        storage::write_config_file(SETTINGS_FILE, &contents);
+    }
+}
+
+fn parse_into<T: std::str::FromStr + PartialOrd>(
+    value: &str,
+    target: &mut T,
+    range: std::ops::RangeInclusive<T>,
+) -> bool {
+    match value.parse() {
+        Ok(v) if range.contains(&v) => {
+            *target = v;
+            true
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,332 @@
+use macroquad::prelude::*;
+
+use crate::input::{Action, Input};
+use crate::physics::Profile;
+use crate::settings::{AnswerReveal, Settings, MAX_LIVES, TEXT_SCALES, WINDOW_SIZES};
+use crate::theme::{self, Theme};
+use crate::{draw_centered_text, SCREEN_HEIGHT, SCREEN_WIDTH};
+
+/// What the settings screen wants the caller to do next.
+pub enum SettingsEvent {
+    None,
+    Controls,
+    Close,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Row {
+    WindowSize,
+    Fullscreen,
+    StartingLives,
+    DefaultOperation,
+    TextScale,
+    Theme,
+    DyslexicFont,
+    AnswerReveal,
+    Manipulatives,
+    Physics,
+    Fuel,
+    MusicVolume,
+    SfxVolume,
+    Controls,
+    Back,
+}
+
+const ROWS: [Row; 15] = [
+    Row::WindowSize,
+    Row::Fullscreen,
+    Row::StartingLives,
+    Row::DefaultOperation,
+    Row::TextScale,
+    Row::Theme,
+    Row::DyslexicFont,
+    Row::AnswerReveal,
+    Row::Manipulatives,
+    Row::Physics,
+    Row::Fuel,
+    Row::MusicVolume,
+    Row::SfxVolume,
+    Row::Controls,
+    Row::Back,
+];
+
+const FIRST_ROW_Y: f32 = 110.0;
+const ROW_SPACING: f32 = 39.0;
+// The list ends above the help line, and scrolls if it runs longer.
+const LIST_BOTTOM: f32 = SCREEN_HEIGHT - 60.0;
+const VOLUME_STEP: f32 = 0.1;
+
+// How the rows fit at the player's text scale.
+struct RowLayout {
+    scale: f32,
+    two_lines: bool, // Values go under their labels when they don't fit beside them.
+    spacing: f32,
+    visible: usize,
+}
+
+impl RowLayout {
+    // The rectangle of the `slot`th row on screen.
+    fn rect(&self, slot: usize) -> Rect {
+        let width = (660.0 * self.scale).min(SCREEN_WIDTH - 40.0);
+        Rect::new(
+            (SCREEN_WIDTH - width) / 2.0,
+            FIRST_ROW_Y - 32.0 + slot as f32 * self.spacing,
+            width,
+            self.spacing - 6.0,
+        )
+    }
+}
+
+// Moves `index` one step left or right through `len` options, wrapping around.
+fn cycle(index: usize, len: usize, step: i32) -> usize {
+    (index as i32 + step).rem_euclid(len as i32) as usize
+}
+
+fn volume_text(volume: f32) -> String {
+    format!("< {}% >", (volume * 100.0).round())
+}
+
+/// Edits the player's settings and saves them on the way out.
+pub struct SettingsScreen {
+    selected: usize,
+    first: usize, // The row at the top of the screen.
+    font_loaded: bool,
+}
+
+impl SettingsScreen {
+    /// `font_loaded` says whether the dyslexia-friendly font file was found.
+    pub fn new(font_loaded: bool) -> SettingsScreen {
+        SettingsScreen {
+            selected: 0,
+            first: 0,
+            font_loaded,
+        }
+    }
+
+    pub fn update(&mut self, input: &Input, settings: &mut Settings) -> SettingsEvent {
+        if input.is_pressed(Action::Up) && self.selected > 0 {
+            self.selected -= 1;
+        } else if input.is_pressed(Action::Down) && self.selected + 1 < ROWS.len() {
+            self.selected += 1;
+        }
+        let mut activate = input.is_pressed(Action::Confirm);
+        let layout = self.layout(settings);
+        let shown = layout.visible.min(ROWS.len() - self.first);
+        if let Some(slot) = (0..shown).find(|&slot| input.tapped(layout.rect(slot))) {
+            self.selected = self.first + slot;
+            activate = true;
+        }
+        self.first = self.first.clamp(
+            (self.selected + 1).saturating_sub(layout.visible),
+            self.selected,
+        );
+        if input.is_pressed(Action::Back) {
+            return self.close(settings);
+        }
+
+        // Left steps back, Right or Confirm steps forward.
+        let step = if input.is_pressed(Action::MoveLeft) {
+            -1
+        } else if input.is_pressed(Action::MoveRight) || activate {
+            1
+        } else {
+            0
+        };
+        match ROWS[self.selected] {
+            Row::Controls if activate => return SettingsEvent::Controls,
+            Row::Back if activate => return self.close(settings),
+            Row::Controls | Row::Back => {}
+            _ if step == 0 => {}
+            Row::WindowSize => {
+                let current = WINDOW_SIZES
+                    .iter()
+                    .position(|&size| size == (settings.window_width, settings.window_height))
+                    .unwrap_or(1);
+                let (w, h) = WINDOW_SIZES[cycle(current, WINDOW_SIZES.len(), step)];
+                settings.window_width = w;
+                settings.window_height = h;
+                request_new_screen_size(w as f32, h as f32);
+            }
+            Row::Fullscreen => {
+                settings.fullscreen = !settings.fullscreen;
+                set_fullscreen(settings.fullscreen);
+            }
+            Row::StartingLives => {
+                settings.starting_lives = (settings.starting_lives + step).clamp(1, MAX_LIVES);
+            }
+            Row::DefaultOperation => {
+                settings.default_operation = if step < 0 {
+                    settings.default_operation.previous()
+                } else {
+                    settings.default_operation.next()
+                };
+            }
+            Row::TextScale => {
+                let current = TEXT_SCALES
+                    .iter()
+                    .position(|&scale| scale == settings.text_scale)
+                    .unwrap_or(0);
+                settings.text_scale = TEXT_SCALES[cycle(current, TEXT_SCALES.len(), step)];
+            }
+            Row::Theme => {
+                let current = Theme::ALL
+                    .iter()
+                    .position(|&theme| theme == settings.theme)
+                    .unwrap_or(0);
+                settings.theme = Theme::ALL[cycle(current, Theme::ALL.len(), step)];
+            }
+            Row::DyslexicFont => settings.dyslexic_font = !settings.dyslexic_font,
+            Row::AnswerReveal => {
+                let current = AnswerReveal::ALL
+                    .iter()
+                    .position(|&reveal| reveal == settings.answer_reveal)
+                    .unwrap_or(0);
+                settings.answer_reveal =
+                    AnswerReveal::ALL[cycle(current, AnswerReveal::ALL.len(), step)];
+            }
+            Row::Manipulatives => settings.manipulatives = !settings.manipulatives,
+            Row::Physics => {
+                // "Match the scenery" comes before the fixed profiles.
+                let current = match settings.physics {
+                    None => 0,
+                    Some(profile) => {
+                        1 + Profile::ALL.iter().position(|&p| p == profile).unwrap_or(0)
+                    }
+                };
+                let next = cycle(current, Profile::ALL.len() + 1, step);
+                settings.physics = next.checked_sub(1).map(|i| Profile::ALL[i]);
+            }
+            Row::Fuel => settings.fuel = !settings.fuel,
+            Row::MusicVolume => {
+                settings.music_volume =
+                    (settings.music_volume + step as f32 * VOLUME_STEP).clamp(0.0, 1.0);
+            }
+            Row::SfxVolume => {
+                settings.sfx_volume =
+                    (settings.sfx_volume + step as f32 * VOLUME_STEP).clamp(0.0, 1.0);
+            }
+        }
+        SettingsEvent::None
+    }
+
+    fn close(&mut self, settings: &Settings) -> SettingsEvent {
+        settings.save();
+        self.selected = 0;
+        self.first = 0;
+        SettingsEvent::Close
+    }
+
+    // A row's label and its current value.
+    fn row_text(&self, row: Row, settings: &Settings) -> (&'static str, String) {
+        match row {
+            Row::WindowSize => (
+                "Window size",
+                format!("< {} x {} >", settings.window_width, settings.window_height),
+            ),
+            Row::Fullscreen => (
+                "Fullscreen",
+                if settings.fullscreen { "On" } else { "Off" }.to_string(),
+            ),
+            Row::StartingLives => ("Starting lives", format!("< {} >", settings.starting_lives)),
+            Row::DefaultOperation => (
+                "Default operation",
+                format!("< {} >", settings.default_operation.name()),
+            ),
+            Row::TextScale => ("Text size", format!("< {}x >", settings.text_scale)),
+            Row::Theme => ("Theme", format!("< {} >", settings.theme.name())),
+            Row::DyslexicFont => (
+                "Dyslexia-friendly font",
+                match (settings.dyslexic_font, self.font_loaded) {
+                    (false, _) => "Off",
+                    (true, true) => "On",
+                    (true, false) => "On (font file missing)",
+                }
+                .to_string(),
+            ),
+            Row::AnswerReveal => (
+                "Show right answer",
+                format!("< {} >", settings.answer_reveal.name()),
+            ),
+            Row::Manipulatives => (
+                "Counting pictures",
+                if settings.manipulatives { "On" } else { "Off" }.to_string(),
+            ),
+            Row::Physics => (
+                "Jetpack physics",
+                format!(
+                    "< {} >",
+                    settings.physics.map_or("Match the scenery", Profile::name)
+                ),
+            ),
+            Row::Fuel => (
+                "Jetpack fuel",
+                if settings.fuel { "On" } else { "Off" }.to_string(),
+            ),
+            Row::MusicVolume => ("Music volume", volume_text(settings.music_volume)),
+            Row::SfxVolume => ("Effects volume", volume_text(settings.sfx_volume)),
+            Row::Controls => ("Controls...", String::new()),
+            Row::Back => ("Save and go back", String::new()),
+        }
+    }
+
+    fn layout(&self, settings: &Settings) -> RowLayout {
+        let scale = theme::text_scale();
+        let size = theme::scaled(30);
+        let (label_width, value_width) = ROWS.iter().fold((0.0, 0.0), |(l, v), &row| {
+            let (label, value) = self.row_text(row, settings);
+            (
+                theme::measure_text(label, size).width.max(l),
+                theme::measure_text(&value, size).width.max(v),
+            )
+        });
+        let width = (660.0 * scale).min(SCREEN_WIDTH - 40.0);
+        let two_lines = label_width > 340.0 * scale || 360.0 * scale + value_width > width - 10.0;
+        let spacing = ROW_SPACING * scale * if two_lines { 2.0 } else { 1.0 };
+        let room = ((LIST_BOTTOM - (FIRST_ROW_Y - 32.0)) / spacing) as usize;
+        RowLayout {
+            scale,
+            two_lines,
+            spacing,
+            visible: room.clamp(1, ROWS.len()),
+        }
+    }
+
+    pub fn draw(&self, settings: &Settings) {
+        let palette = theme::palette();
+        clear_background(palette.background);
+        draw_centered_text("Settings", 70.0, 50, palette.text);
+
+        let layout = self.layout(settings);
+        let size = theme::scaled(30);
+        let last = (self.first + layout.visible).min(ROWS.len());
+        for (slot, i) in (self.first..last).enumerate() {
+            let rect = layout.rect(slot);
+            if i == self.selected {
+                draw_rectangle(
+                    rect.x,
+                    rect.y,
+                    rect.w,
+                    rect.h,
+                    Color::new(1.0, 1.0, 1.0, 0.5),
+                );
+            }
+            let (label, value) = self.row_text(ROWS[i], settings);
+            let y = rect.y + 32.0 * layout.scale;
+            theme::draw_text(label, rect.x + 10.0, y, size, palette.text);
+            if layout.two_lines {
+                let y = y + ROW_SPACING * layout.scale;
+                theme::draw_text(&value, rect.x + 40.0, y, size, palette.muted);
+            } else {
+                let x = rect.x + 360.0 * layout.scale;
+                theme::draw_text(&value, x, y, size, palette.muted);
+            }
+        }
+
+        draw_centered_text(
+            "Up/Down to choose, Left/Right to change",
+            SCREEN_HEIGHT - 30.0,
+            24,
+            palette.muted,
+        );
+    }
+}
@@ -0,0 +1,72 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Name of the per-user folder the game keeps its files in.
+const APP_DIR: &str = "mathnauts";
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(PathBuf::from)
+}
+
+/// Returns the platform's per-user data folder for the game, if one can be found.
+/// Linux uses $XDG_DATA_HOME (or ~/.local/share), macOS uses
+/// ~/Library/Application Support and Windows uses %APPDATA%.
+pub fn data_dir() -> Option<PathBuf> {
+    let base = if cfg!(target_os = "windows") {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home_dir().map(|home| home.join("Library/Application Support"))
+    } else {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home_dir().map(|home| home.join(".local/share")))
+    };
+    base.map(|dir| dir.join(APP_DIR))
+}
+
+/// Returns the platform's per-user config folder for the game, if one can be found.
+/// Linux uses $XDG_CONFIG_HOME (or ~/.config), macOS uses
+/// ~/Library/Preferences and Windows uses %APPDATA%.
+pub fn config_dir() -> Option<PathBuf> {
+    let base = if cfg!(target_os = "windows") {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home_dir().map(|home| home.join("Library/Preferences"))
+    } else {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home_dir().map(|home| home.join(".config")))
+    };
+    base.map(|dir| dir.join(APP_DIR))
+}
+
+/// Reads a file from the data folder. Missing files just give `None`.
+pub fn read_data_file(name: &str) -> Option<String> {
+    fs::read_to_string(data_dir()?.join(name)).ok()
+}
+
+/// Writes a file into the data folder, creating the folder on first use.
+pub fn write_data_file(name: &str, contents: &str) {
+    if let Some(dir) = data_dir() {
+        write_file(&dir, name, contents);
+    }
+}
+
+/// Reads a file from the config folder. Missing files just give `None`.
+pub fn read_config_file(name: &str) -> Option<String> {
+    fs::read_to_string(config_dir()?.join(name)).ok()
+}
+
+/// Writes a file into the config folder, creating the folder on first use.
+pub fn write_config_file(name: &str, contents: &str) {
+    if let Some(dir) = config_dir() {
+        write_file(&dir, name, contents);
+    }
+}
+
+// Saving is best-effort: the game keeps running if the disk is read-only.
+fn write_file(dir: &Path, name: &str, contents: &str) {
+    if let Err(err) = fs::create_dir_all(dir).and_then(|_| fs::write(dir.join(name), contents)) {
+        eprintln!("Could not save {}: {}", name, err);
+    }
+}
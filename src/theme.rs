@@ -0,0 +1,178 @@
+use macroquad::prelude::*;
+use std::cell::RefCell;
+
+use crate::environment::Environment;
+use crate::settings::Settings;
+
+/// The dyslexia-friendly font, inside the assets folder. Noto Sans ships
+/// there (see `dyslexic-LICENSE.txt`); OpenDyslexic or any other TTF can
+/// be dropped in instead.
+pub const DYSLEXIC_FONT: &str = "fonts/dyslexic.ttf";
+
+/// Color schemes the player can pick on the settings screen.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Theme {
+    Classic,
+    HighContrast,
+    ColorBlind, // Avoids telling things apart by red and green alone.
+}
+
+/// Colors for the parts of the screen a theme changes.
+#[derive(Clone, Copy)]
+pub struct Palette {
+    pub background: Color,
+    pub text: Color,
+    pub muted: Color, // Hints and unselected entries.
+    pub ground: Color,
+    pub ground_text: Color,
+    pub lives: Color,
+    pub good: Color,
+    pub bad: Color,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Classic, Theme::HighContrast, Theme::ColorBlind];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Theme::Classic => "Classic",
+            Theme::HighContrast => "High contrast",
+            Theme::ColorBlind => "Color-blind friendly",
+        }
+    }
+
+    /// Name used in the settings file.
+    pub fn id(self) -> &'static str {
+        match self {
+            Theme::Classic => "classic",
+            Theme::HighContrast => "high_contrast",
+            Theme::ColorBlind => "color_blind",
+        }
+    }
+
+    pub fn palette(self) -> Palette {
+        match self {
+            Theme::Classic => Palette {
+                background: SKYBLUE,
+                text: BLACK,
+                muted: DARKGRAY,
+                ground: BROWN,
+                ground_text: WHITE,
+                lives: RED,
+                good: GREEN,
+                bad: RED,
+            },
+            Theme::HighContrast => Palette {
+                background: BLACK,
+                text: WHITE,
+                muted: YELLOW,
+                ground: LIGHTGRAY,
+                ground_text: BLACK,
+                lives: BLACK,
+                good: WHITE,
+                bad: YELLOW,
+            },
+            // Okabe-Ito colors: orange and blue instead of red and green.
+            Theme::ColorBlind => Palette {
+                background: Color::new(0.80, 0.90, 1.0, 1.0),
+                text: BLACK,
+                muted: Color::new(0.25, 0.25, 0.30, 1.0),
+                ground: Color::new(0.45, 0.35, 0.25, 1.0),
+                ground_text: WHITE,
+                lives: Color::new(0.90, 0.62, 0.0, 1.0),
+                good: Color::new(0.0, 0.45, 0.70, 1.0),
+                bad: Color::new(0.84, 0.37, 0.0, 1.0),
+            },
+        }
+    }
+}
+
+// The look every text and HUD draw uses this frame. Drawing happens all over
+// the game, so it is kept here rather than passed to each function.
+struct Style {
+    theme: Theme,
+    environment: Option<Environment>, // Recolors the palette during a run.
+    text_scale: f32,
+    font: Option<Font>,
+}
+
+impl Style {
+    // High contrast keeps its plain colors, so environments don't apply.
+    fn scenery(&self) -> Option<Environment> {
+        self.environment
+            .filter(|_| self.theme != Theme::HighContrast)
+    }
+}
+
+thread_local! {
+    static STYLE: RefCell<Style> = const {
+        RefCell::new(Style {
+            theme: Theme::Classic,
+            environment: None,
+            text_scale: 1.0,
+            font: None,
+        })
+    };
+}
+
+/// Switches to the theme, text scale and font chosen in `settings`. The
+/// dyslexic font is only used if it could be loaded.
+pub fn apply(settings: &Settings, dyslexic_font: Option<&Font>) {
+    STYLE.with_borrow_mut(|style| {
+        style.theme = settings.theme;
+        style.text_scale = settings.text_scale;
+        style.font = dyslexic_font.filter(|_| settings.dyslexic_font).cloned();
+    });
+}
+
+/// Sets the environment of the run being played, or None outside a run.
+pub fn set_environment(environment: Option<Environment>) {
+    STYLE.with_borrow_mut(|style| style.environment = environment);
+}
+
+/// The environment to draw scenery for, if the theme shows scenery.
+pub fn scenery() -> Option<Environment> {
+    STYLE.with_borrow(|style| style.scenery())
+}
+
+pub fn palette() -> Palette {
+    STYLE.with_borrow(|style| match style.scenery() {
+        Some(environment) => environment.palette(style.theme.palette()),
+        None => style.theme.palette(),
+    })
+}
+
+/// The player's text scale, for sizing what the text sits in.
+pub fn text_scale() -> f32 {
+    STYLE.with_borrow(|style| style.text_scale)
+}
+
+/// A font size multiplied by the player's text scale.
+pub fn scaled(font_size: u16) -> u16 {
+    STYLE.with_borrow(|style| (font_size as f32 * style.text_scale).round() as u16)
+}
+
+/// Measures text in the current font.
+pub fn measure_text(text: &str, font_size: u16) -> TextDimensions {
+    STYLE.with_borrow(|style| {
+        macroquad::text::measure_text(text, style.font.as_ref(), font_size, 1.0)
+    })
+}
+
+/// Draws text in the current font. Callers scale `font_size` with `scaled`
+/// where the layout has room for bigger text.
+pub fn draw_text(text: &str, x: f32, y: f32, font_size: u16, color: Color) {
+    STYLE.with_borrow(|style| {
+        draw_text_ex(
+            text,
+            x,
+            y,
+            TextParams {
+                font: style.font.as_ref(),
+                font_size,
+                color,
+                ..Default::default()
+            },
+        );
+    });
+}
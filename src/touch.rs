@@ -0,0 +1,185 @@
+use macroquad::prelude::*;
+
+use crate::input::{Action, Input};
+use crate::theme;
+use crate::{MultipleChoice, Player, PlayerState, GROUND_Y, SCREEN_WIDTH};
+
+/// How the astronaut is flown on a touchscreen.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TouchScheme {
+    /// Tap a shuttle and the astronaut flies there on its own.
+    TapToFly,
+    /// On-screen left, right and thrust buttons.
+    Buttons,
+}
+
+impl TouchScheme {
+    pub const ALL: [TouchScheme; 2] = [TouchScheme::TapToFly, TouchScheme::Buttons];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TouchScheme::TapToFly => "Tap a shuttle",
+            TouchScheme::Buttons => "On-screen buttons",
+        }
+    }
+
+    /// Name used in the settings file.
+    pub fn id(self) -> &'static str {
+        match self {
+            TouchScheme::TapToFly => "tap_to_fly",
+            TouchScheme::Buttons => "buttons",
+        }
+    }
+
+    pub fn toggled(self) -> TouchScheme {
+        match self {
+            TouchScheme::TapToFly => TouchScheme::Buttons,
+            TouchScheme::Buttons => TouchScheme::TapToFly,
+        }
+    }
+}
+
+// Size of the virtual buttons and how far ahead the autopilot predicts the fall.
+const BUTTON_SIZE: f32 = 80.0;
+const AUTOPILOT_LOOKAHEAD: f32 = 12.0; // frames
+const AUTOPILOT_DEADZONE: f32 = 40.0; // pixels above the target before pushing down
+
+// Hitbox of a shuttle, matching the collision check in `main`.
+const SHUTTLE_WIDTH: f32 = 100.0;
+const SHUTTLE_HEIGHT: f32 = 80.0;
+// Keep the movement buttons clear of the alien on the left edge.
+const ALIEN_SAFE_X: f32 = 210.0;
+
+fn button_rects() -> [(Action, Rect, &'static str); 3] {
+    let y = GROUND_Y - 25.0;
+    [
+        (
+            Action::MoveLeft,
+            Rect::new(ALIEN_SAFE_X, y, BUTTON_SIZE, BUTTON_SIZE),
+            "<",
+        ),
+        (
+            Action::MoveRight,
+            Rect::new(
+                ALIEN_SAFE_X + BUTTON_SIZE + 15.0,
+                y,
+                BUTTON_SIZE,
+                BUTTON_SIZE,
+            ),
+            ">",
+        ),
+        (
+            Action::Thrust,
+            Rect::new(
+                SCREEN_WIDTH - BUTTON_SIZE - 20.0,
+                y,
+                BUTTON_SIZE,
+                BUTTON_SIZE,
+            ),
+            "^",
+        ),
+    ]
+}
+
+/// Turns taps and held fingers into movement actions during play.
+pub struct TouchControls {
+    pub scheme: TouchScheme,
+    // Centre of the shuttle (or spot) the autopilot is flying to.
+    target: Option<Vec2>,
+}
+
+impl TouchControls {
+    pub fn new(scheme: TouchScheme) -> TouchControls {
+        TouchControls {
+            scheme,
+            target: None,
+        }
+    }
+
+    /// Forgets the autopilot target, e.g. when a new question appears.
+    pub fn clear_target(&mut self) {
+        self.target = None;
+    }
+
+    /// Feeds movement actions into `input` before the player is updated.
+    pub fn update(&mut self, input: &mut Input, player: &Player, choices: &[MultipleChoice]) {
+        match self.scheme {
+            TouchScheme::Buttons => {
+                for (action, rect, _) in button_rects() {
+                    if input.touching(rect) {
+                        input.hold(action);
+                    }
+                }
+            }
+            TouchScheme::TapToFly => {
+                if let Some(tap) = input.tap() {
+                    // Snap taps near a shuttle onto its centre.
+                    self.target = Some(
+                        choices
+                            .iter()
+                            .map(|c| vec2(c.x + SHUTTLE_WIDTH / 2.0, c.y + SHUTTLE_HEIGHT / 2.0))
+                            .find(|centre| centre.distance(tap) < SHUTTLE_WIDTH)
+                            .unwrap_or(tap),
+                    );
+                }
+                if player.state == PlayerState::Fail {
+                    self.target = None;
+                }
+                if let Some(target) = self.target {
+                    steer_towards(input, player, target);
+                }
+            }
+        }
+    }
+
+    /// Draws the virtual buttons or the autopilot target marker.
+    pub fn draw(&self, input: &Input) {
+        match self.scheme {
+            TouchScheme::Buttons => {
+                for (action, rect, label) in button_rects() {
+                    let alpha = if input.is_down(action) { 0.6 } else { 0.3 };
+                    draw_rectangle(
+                        rect.x,
+                        rect.y,
+                        rect.w,
+                        rect.h,
+                        Color::new(1.0, 1.0, 1.0, alpha),
+                    );
+                    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 3.0, WHITE);
+                    let dims = theme::measure_text(label, 50);
+                    theme::draw_text(
+                        label,
+                        rect.x + (rect.w - dims.width) / 2.0,
+                        rect.y + (rect.h + dims.height) / 2.0,
+                        50,
+                        BLACK,
+                    );
+                }
+            }
+            TouchScheme::TapToFly => {
+                if let Some(target) = self.target {
+                    draw_circle_lines(target.x, target.y, 30.0, 3.0, YELLOW);
+                }
+            }
+        }
+    }
+}
+
+/// Presses the same left/right/thrust actions a player would to reach
+/// `target`, so the astronaut obeys the usual physics.
+fn steer_towards(input: &mut Input, player: &Player, target: Vec2) {
+    let centre_x = player.x + player.width / 2.0;
+    let centre_y = player.y + player.height / 2.0;
+    if target.x < centre_x - 10.0 {
+        input.hold(Action::MoveLeft);
+    } else if target.x > centre_x + 10.0 {
+        input.hold(Action::MoveRight);
+    }
+    // Fire the jetpack whenever the astronaut would sink below the target soon.
+    if centre_y + player.vy * AUTOPILOT_LOOKAHEAD > target.y {
+        input.hold(Action::Thrust);
+    } else if centre_y + player.vy * AUTOPILOT_LOOKAHEAD < target.y - AUTOPILOT_DEADZONE {
+        // Only does anything where the jetpack can push downwards.
+        input.hold(Action::Down);
+    }
+}
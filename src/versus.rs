@@ -0,0 +1,62 @@
+use macroquad::prelude::*;
+
+use crate::theme;
+use crate::{draw_centered_text, SCREEN_HEIGHT};
+
+/// Player two's own name, score, lives and fuel in a versus run. Player one
+/// keeps using the run's usual state; player two's astronaut is kept
+/// alongside player one's.
+pub struct Rival {
+    pub name: String,
+    pub score: i32,
+    pub lives: i32,
+    pub fuel: f32,
+}
+
+impl Rival {
+    pub fn new(name: String, lives: i32) -> Rival {
+        Rival {
+            name,
+            score: 0,
+            lives,
+            fuel: 1.0,
+        }
+    }
+}
+
+/// The score questions and aliens are tuned to: the leader's, so the game
+/// doesn't go easy on whoever is ahead.
+pub fn level(score: i32, rival: Option<&Rival>) -> i32 {
+    rival.map_or(score, |rival| score.max(rival.score))
+}
+
+/// The Game Over screen of a versus run: who won and both scores. Running
+/// out of lives loses; if both run out together, the higher score wins.
+pub fn draw_results(name: &str, score: i32, lives: i32, rival: &Rival) {
+    let palette = theme::palette();
+    clear_background(palette.background);
+    let winner = match (lives > 0, rival.lives > 0) {
+        (true, false) => Some(name),
+        (false, true) => Some(rival.name.as_str()),
+        _ if score > rival.score => Some(name),
+        _ if rival.score > score => Some(rival.name.as_str()),
+        _ => None,
+    };
+    let title = match winner {
+        Some(winner) => format!("{} wins!", winner),
+        None => "It's a draw!".to_string(),
+    };
+    draw_centered_text(&title, SCREEN_HEIGHT / 2.0 - 60.0, 60, palette.good);
+    draw_centered_text(
+        &format!("{}: {}", name, score),
+        SCREEN_HEIGHT / 2.0 + 30.0,
+        40,
+        palette.text,
+    );
+    draw_centered_text(
+        &format!("{}: {}", rival.name, rival.score),
+        SCREEN_HEIGHT / 2.0 + 80.0,
+        40,
+        palette.text,
+    );
+}
@@ -0,0 +1,59 @@
+use macroquad::prelude::*;
+
+use crate::{SCREEN_HEIGHT, SCREEN_WIDTH};
+
+/// The part of the window the virtual screen is scaled into, in window
+/// pixels. The aspect ratio is kept, leaving bars at the sides or top.
+fn letterbox() -> Rect {
+    let scale = (screen_width() / SCREEN_WIDTH).min(screen_height() / SCREEN_HEIGHT);
+    let w = SCREEN_WIDTH * scale;
+    let h = SCREEN_HEIGHT * scale;
+    Rect::new(
+        (screen_width() - w) / 2.0,
+        (screen_height() - h) / 2.0,
+        w,
+        h,
+    )
+}
+
+/// Starts drawing in virtual coordinates for this frame.
+pub fn begin() {
+    begin_shaken(Vec2::ZERO);
+}
+
+/// Like `begin`, with everything moved by `offset` for screen shake.
+pub fn begin_shaken(offset: Vec2) {
+    let area = letterbox();
+    // The viewport is in physical pixels; the bars are the same size
+    // top and bottom, so the flipped GL origin doesn't matter.
+    let dpi = screen_dpi_scale();
+    set_camera(&Camera2D {
+        target: vec2(SCREEN_WIDTH / 2.0, SCREEN_HEIGHT / 2.0) - offset,
+        zoom: vec2(2.0 / SCREEN_WIDTH, 2.0 / SCREEN_HEIGHT),
+        viewport: Some((
+            (area.x * dpi) as i32,
+            (area.y * dpi) as i32,
+            (area.w * dpi) as i32,
+            (area.h * dpi) as i32,
+        )),
+        ..Default::default()
+    });
+}
+
+/// Goes back to window coordinates and blacks out the letterbox bars,
+/// since `clear_background` paints the whole window.
+pub fn end() {
+    set_default_camera();
+    let area = letterbox();
+    let (w, h) = (screen_width(), screen_height());
+    draw_rectangle(0.0, 0.0, area.x, h, BLACK);
+    draw_rectangle(area.x + area.w, 0.0, w - area.x - area.w, h, BLACK);
+    draw_rectangle(0.0, 0.0, w, area.y, BLACK);
+    draw_rectangle(0.0, area.y + area.h, w, h - area.y - area.h, BLACK);
+}
+
+/// Converts a mouse or touch position in the window to virtual coordinates.
+pub fn to_virtual(point: Vec2) -> Vec2 {
+    let area = letterbox();
+    (point - area.point()) * (SCREEN_WIDTH / area.w)
+}